@@ -5,63 +5,491 @@ DON'T CHANGE THIS FILE MANUALLY, IT WILL GO AWAY!!!
 */
 use crate::builtin::Builtin;
 
-pub static BUILTINS: [Builtin; 18] = [
+pub static BUILTINS: [Builtin; 148] = [
+    Builtin {
+        name: "Dedent",
+        func: crate::value::token::tokay_token_dedent,
+    },
     Builtin {
         name: "Identifier",
         func: crate::value::token::tokay_token_identifier,
     },
+    Builtin {
+        name: "Indent",
+        func: crate::value::token::tokay_token_indent,
+    },
     Builtin {
         name: "Integer",
         func: crate::value::token::tokay_token_integer,
     },
+    Builtin {
+        name: "Samedent",
+        func: crate::value::token::tokay_token_samedent,
+    },
     Builtin {
         name: "Word",
         func: crate::value::token::tokay_token_word,
     },
+    Builtin {
+        name: "adler32",
+        func: crate::builtin::tokay_function_adler32,
+    },
+    Builtin {
+        name: "all",
+        func: crate::builtin::tokay_function_all,
+    },
+    Builtin {
+        name: "alt",
+        func: crate::value::combinator::tokay_function_alt,
+    },
+    Builtin {
+        name: "any",
+        func: crate::builtin::tokay_function_any,
+    },
+    Builtin {
+        name: "apply",
+        func: crate::builtin::tokay_function_apply,
+    },
+    Builtin {
+        name: "argmax",
+        func: crate::builtin::tokay_function_argmax,
+    },
+    Builtin {
+        name: "argmin",
+        func: crate::builtin::tokay_function_argmin,
+    },
     Builtin {
         name: "ast",
         func: crate::compiler::ast::tokay_function_ast,
     },
+    Builtin {
+        name: "ast_map",
+        func: crate::compiler::ast::tokay_function_ast_map,
+    },
+    Builtin {
+        name: "ast_match",
+        func: crate::compiler::ast::tokay_function_ast_match,
+    },
     Builtin {
         name: "ast_print",
         func: crate::compiler::ast::tokay_function_ast_print,
     },
+    Builtin {
+        name: "ast_prune",
+        func: crate::compiler::ast::tokay_function_ast_prune,
+    },
+    Builtin {
+        name: "ast_rewrite",
+        func: crate::compiler::ast::tokay_function_ast_rewrite,
+    },
+    Builtin {
+        name: "band",
+        func: crate::builtin::tokay_function_band,
+    },
+    Builtin {
+        name: "bnot",
+        func: crate::builtin::tokay_function_bnot,
+    },
+    Builtin {
+        name: "bor",
+        func: crate::builtin::tokay_function_bor,
+    },
+    Builtin {
+        name: "bxor",
+        func: crate::builtin::tokay_function_bxor,
+    },
+    Builtin {
+        name: "capture_range",
+        func: crate::builtin::tokay_function_capture_range,
+    },
+    Builtin {
+        name: "cc_negate",
+        func: crate::value::token::tokay_function_cc_negate,
+    },
+    Builtin {
+        name: "ccl",
+        func: crate::value::token::tokay_function_ccl,
+    },
+    Builtin {
+        name: "ceil",
+        func: crate::builtin::tokay_function_ceil,
+    },
+    Builtin {
+        name: "cell",
+        func: crate::value::cell::tokay_function_cell,
+    },
+    Builtin {
+        name: "cell_get",
+        func: crate::value::cell::tokay_function_cell_get,
+    },
+    Builtin {
+        name: "cell_set",
+        func: crate::value::cell::tokay_function_cell_set,
+    },
+    Builtin {
+        name: "chars",
+        func: crate::builtin::tokay_function_chars,
+    },
     Builtin {
         name: "chr",
         func: crate::builtin::tokay_function_chr,
     },
+    Builtin {
+        name: "chunk_by",
+        func: crate::builtin::tokay_function_chunk_by,
+    },
+    Builtin {
+        name: "clamp",
+        func: crate::builtin::tokay_function_clamp,
+    },
+    Builtin {
+        name: "count_leading",
+        func: crate::builtin::tokay_function_count_leading,
+    },
+    Builtin {
+        name: "count_trailing",
+        func: crate::builtin::tokay_function_count_trailing,
+    },
+    Builtin {
+        name: "crc32",
+        func: crate::builtin::tokay_function_crc32,
+    },
+    Builtin {
+        name: "dedent",
+        func: crate::builtin::tokay_function_dedent,
+    },
     Builtin {
         name: "dict",
         func: crate::value::dict::Dict::tokay_method_dict_new,
     },
+    Builtin {
+        name: "dict_items",
+        func: crate::value::dict::Dict::tokay_method_dict_items,
+    },
+    Builtin {
+        name: "dict_keys",
+        func: crate::value::dict::Dict::tokay_method_dict_keys,
+    },
     Builtin {
         name: "dict_update",
         func: crate::value::dict::Dict::tokay_method_dict_update,
     },
+    Builtin {
+        name: "dict_values",
+        func: crate::value::dict::Dict::tokay_method_dict_values,
+    },
+    Builtin {
+        name: "digits",
+        func: crate::builtin::tokay_function_digits,
+    },
+    Builtin {
+        name: "divmod",
+        func: crate::builtin::tokay_function_divmod,
+    },
+    Builtin {
+        name: "drop_while",
+        func: crate::builtin::tokay_function_drop_while,
+    },
+    Builtin {
+        name: "dump_captures",
+        func: crate::builtin::tokay_function_dump_captures,
+    },
+    Builtin {
+        name: "emit_types",
+        func: crate::compiler::ast::tokay_function_emit_types,
+    },
     Builtin {
         name: "error",
         func: crate::error::tokay_function_error,
     },
+    Builtin {
+        name: "expand_tabs",
+        func: crate::builtin::tokay_function_expand_tabs,
+    },
+    Builtin {
+        name: "expect_type",
+        func: crate::error::tokay_function_expect_type,
+    },
+    Builtin {
+        name: "field",
+        func: crate::builtin::tokay_function_field,
+    },
+    Builtin {
+        name: "find",
+        func: crate::builtin::tokay_function_find,
+    },
+    Builtin {
+        name: "find_all",
+        func: crate::builtin::tokay_function_find_all,
+    },
+    Builtin {
+        name: "find_index",
+        func: crate::builtin::tokay_function_find_index,
+    },
+    Builtin {
+        name: "first_set",
+        func: crate::value::parselet::tokay_function_first_set,
+    },
+    Builtin {
+        name: "floor",
+        func: crate::builtin::tokay_function_floor,
+    },
+    Builtin {
+        name: "from_chars",
+        func: crate::builtin::tokay_function_from_chars,
+    },
+    Builtin {
+        name: "from_digits",
+        func: crate::builtin::tokay_function_from_digits,
+    },
+    Builtin {
+        name: "gcd",
+        func: crate::builtin::tokay_function_gcd,
+    },
+    Builtin {
+        name: "gensym",
+        func: crate::builtin::tokay_function_gensym,
+    },
+    Builtin {
+        name: "get_path",
+        func: crate::builtin::tokay_function_get_path,
+    },
+    Builtin {
+        name: "has_keys",
+        func: crate::builtin::tokay_function_has_keys,
+    },
+    Builtin {
+        name: "indent",
+        func: crate::builtin::tokay_function_indent,
+    },
+    Builtin {
+        name: "interleave",
+        func: crate::builtin::tokay_function_interleave,
+    },
+    Builtin {
+        name: "is_empty",
+        func: crate::builtin::tokay_function_is_empty,
+    },
+    Builtin {
+        name: "is_finite",
+        func: crate::builtin::tokay_function_is_finite,
+    },
+    Builtin {
+        name: "is_ident",
+        func: crate::value::token::tokay_function_is_ident,
+    },
+    Builtin {
+        name: "is_leftrec",
+        func: crate::value::parselet::tokay_function_is_leftrec,
+    },
+    Builtin {
+        name: "is_nan",
+        func: crate::builtin::tokay_function_is_nan,
+    },
+    Builtin {
+        name: "is_nullable",
+        func: crate::value::parselet::tokay_function_is_nullable,
+    },
+    Builtin {
+        name: "lcm",
+        func: crate::builtin::tokay_function_lcm,
+    },
+    Builtin {
+        name: "leaves",
+        func: crate::compiler::ast::tokay_function_leaves,
+    },
     Builtin {
         name: "list",
         func: crate::value::list::List::tokay_method_list_new,
     },
+    Builtin {
+        name: "list_pad",
+        func: crate::value::list::List::tokay_method_list_pad,
+    },
+    Builtin {
+        name: "list_pop",
+        func: crate::value::list::List::tokay_method_list_pop,
+    },
     Builtin {
         name: "list_push",
         func: crate::value::list::List::tokay_method_list_push,
     },
+    Builtin {
+        name: "longest",
+        func: crate::value::combinator::tokay_function_longest,
+    },
+    Builtin {
+        name: "lookahead",
+        func: crate::builtin::tokay_function_lookahead,
+    },
+    Builtin {
+        name: "lookahead_not",
+        func: crate::builtin::tokay_function_lookahead_not,
+    },
+    Builtin {
+        name: "lookup",
+        func: crate::builtin::tokay_function_lookup,
+    },
+    Builtin {
+        name: "mark",
+        func: crate::value::mark::tokay_function_mark,
+    },
+    Builtin {
+        name: "merge_strings",
+        func: crate::builtin::tokay_function_merge_strings,
+    },
+    Builtin {
+        name: "missing_keys",
+        func: crate::builtin::tokay_function_missing_keys,
+    },
+    Builtin {
+        name: "node_counts",
+        func: crate::compiler::ast::tokay_function_node_counts,
+    },
+    Builtin {
+        name: "normalize_newlines",
+        func: crate::builtin::tokay_function_normalize_newlines,
+    },
+    Builtin {
+        name: "optional",
+        func: crate::builtin::tokay_function_optional,
+    },
     Builtin {
         name: "ord",
         func: crate::builtin::tokay_function_ord,
     },
+    Builtin {
+        name: "overlaps",
+        func: crate::value::parselet::tokay_function_overlaps,
+    },
+    Builtin {
+        name: "partition",
+        func: crate::builtin::tokay_function_partition,
+    },
+    Builtin {
+        name: "peek_str",
+        func: crate::builtin::tokay_function_peek_str,
+    },
+    Builtin {
+        name: "pipe",
+        func: crate::builtin::tokay_function_pipe,
+    },
     Builtin {
         name: "print",
         func: crate::builtin::tokay_function_print,
     },
+    Builtin {
+        name: "product_of",
+        func: crate::builtin::tokay_function_product_of,
+    },
+    Builtin {
+        name: "profile",
+        func: crate::builtin::tokay_function_profile,
+    },
+    Builtin {
+        name: "quote",
+        func: crate::builtin::tokay_function_quote,
+    },
+    Builtin {
+        name: "repeat_str",
+        func: crate::builtin::tokay_function_repeat_str,
+    },
     Builtin {
         name: "repr",
         func: crate::builtin::tokay_function_repr,
     },
+    Builtin {
+        name: "reset_to",
+        func: crate::value::mark::tokay_function_reset_to,
+    },
+    Builtin {
+        name: "rle",
+        func: crate::builtin::tokay_function_rle,
+    },
+    Builtin {
+        name: "rle_decode",
+        func: crate::builtin::tokay_function_rle_decode,
+    },
+    Builtin {
+        name: "rotate",
+        func: crate::builtin::tokay_function_rotate,
+    },
+    Builtin {
+        name: "round",
+        func: crate::builtin::tokay_function_round,
+    },
+    Builtin {
+        name: "safe_div",
+        func: crate::builtin::tokay_function_safe_div,
+    },
+    Builtin {
+        name: "sample",
+        func: crate::builtin::tokay_function_sample,
+    },
+    Builtin {
+        name: "scan_all",
+        func: crate::builtin::tokay_function_scan_all,
+    },
+    Builtin {
+        name: "scope_define",
+        func: crate::builtin::tokay_function_scope_define,
+    },
+    Builtin {
+        name: "scope_lookup",
+        func: crate::builtin::tokay_function_scope_lookup,
+    },
+    Builtin {
+        name: "scope_new",
+        func: crate::builtin::tokay_function_scope_new,
+    },
+    Builtin {
+        name: "seq",
+        func: crate::value::combinator::tokay_function_seq,
+    },
+    Builtin {
+        name: "set_difference",
+        func: crate::builtin::tokay_function_set_difference,
+    },
+    Builtin {
+        name: "set_intersection",
+        func: crate::builtin::tokay_function_set_intersection,
+    },
+    Builtin {
+        name: "set_path",
+        func: crate::builtin::tokay_function_set_path,
+    },
+    Builtin {
+        name: "set_union",
+        func: crate::builtin::tokay_function_set_union,
+    },
+    Builtin {
+        name: "severity",
+        func: crate::builtin::tokay_function_severity,
+    },
+    Builtin {
+        name: "shl",
+        func: crate::builtin::tokay_function_shl,
+    },
+    Builtin {
+        name: "shr",
+        func: crate::builtin::tokay_function_shr,
+    },
+    Builtin {
+        name: "shuffle",
+        func: crate::builtin::tokay_function_shuffle,
+    },
+    Builtin {
+        name: "skip",
+        func: crate::builtin::tokay_function_skip,
+    },
+    Builtin {
+        name: "str",
+        func: crate::builtin::tokay_function_str,
+    },
+    Builtin {
+        name: "str_ends_with",
+        func: crate::value::str::Str::tokay_method_str_ends_with,
+    },
     Builtin {
         name: "str_join",
         func: crate::value::str::Str::tokay_method_str_join,
@@ -74,8 +502,100 @@ pub static BUILTINS: [Builtin; 18] = [
         name: "str_replace",
         func: crate::value::str::Str::tokay_method_str_replace,
     },
+    Builtin {
+        name: "str_split",
+        func: crate::value::str::Str::tokay_method_str_split,
+    },
+    Builtin {
+        name: "str_starts_with",
+        func: crate::value::str::Str::tokay_method_str_starts_with,
+    },
+    Builtin {
+        name: "str_to_int",
+        func: crate::value::str::Str::tokay_method_str_to_int,
+    },
+    Builtin {
+        name: "str_trim",
+        func: crate::value::str::Str::tokay_method_str_trim,
+    },
+    Builtin {
+        name: "str_trim_end",
+        func: crate::value::str::Str::tokay_method_str_trim_end,
+    },
+    Builtin {
+        name: "str_trim_start",
+        func: crate::value::str::Str::tokay_method_str_trim_start,
+    },
     Builtin {
         name: "str_upper",
         func: crate::value::str::Str::tokay_method_str_upper,
     },
+    Builtin {
+        name: "strip_prefix",
+        func: crate::builtin::tokay_function_strip_prefix,
+    },
+    Builtin {
+        name: "strip_suffix",
+        func: crate::builtin::tokay_function_strip_suffix,
+    },
+    Builtin {
+        name: "take_while",
+        func: crate::builtin::tokay_function_take_while,
+    },
+    Builtin {
+        name: "to_base",
+        func: crate::builtin::tokay_function_to_base,
+    },
+    Builtin {
+        name: "to_camel",
+        func: crate::builtin::tokay_function_to_camel,
+    },
+    Builtin {
+        name: "to_dot",
+        func: crate::compiler::ast::tokay_function_to_dot,
+    },
+    Builtin {
+        name: "to_json",
+        func: crate::builtin::tokay_function_to_json,
+    },
+    Builtin {
+        name: "to_pascal",
+        func: crate::builtin::tokay_function_to_pascal,
+    },
+    Builtin {
+        name: "to_sexpr",
+        func: crate::compiler::ast::tokay_function_to_sexpr,
+    },
+    Builtin {
+        name: "to_snake",
+        func: crate::builtin::tokay_function_to_snake,
+    },
+    Builtin {
+        name: "transpose",
+        func: crate::builtin::tokay_function_transpose,
+    },
+    Builtin {
+        name: "tree_depth",
+        func: crate::compiler::ast::tokay_function_tree_depth,
+    },
+    Builtin {
+        name: "tree_size",
+        func: crate::compiler::ast::tokay_function_tree_size,
+    },
+    Builtin {
+        name: "trie",
+        func: crate::value::trie::tokay_function_trie,
+    },
+    Builtin {
+        name: "unexpand",
+        func: crate::builtin::tokay_function_unexpand,
+    },
+    Builtin {
+        name: "walk",
+        func: crate::compiler::ast::tokay_function_walk,
+    },
+    Builtin {
+        name: "wrap",
+        func: crate::builtin::tokay_function_wrap,
+    },
 ];
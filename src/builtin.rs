@@ -2,7 +2,7 @@
 use crate::_builtins::BUILTINS;
 use crate::value;
 use crate::value::{Dict, Object, RefValue, Value};
-use crate::vm::{Accept, Context, Reject};
+use crate::vm::{Accept, Capture, Context, Reject};
 
 use macros::tokay_function;
 
@@ -128,4 +128,1685 @@ tokay_function!("print(*args)", {
     value!(void).into()
 });
 
+// Calls with a name are compiled into Op::LoadByName; this builtin serves calls by reference.
+tokay_function!("lookup(name)", {
+    let context = context.unwrap();
+
+    match context.runtime.program.lookup(&name.to_string()) {
+        Some(value) => value.into(),
+        None => value!(void).into(),
+    }
+});
+
+tokay_function!("gensym(prefix=void)", {
+    let runtime = &mut context.unwrap().runtime;
+    let prefix = if prefix.is_void() {
+        "g".to_string()
+    } else {
+        prefix.to_string()
+    };
+
+    let name = format!("{}{}", prefix, runtime.gensym);
+    runtime.gensym += 1;
+
+    RefValue::from(name).into()
+});
+
+tokay_function!("profile()", {
+    RefValue::from(context.unwrap().runtime.profile()).into()
+});
+
+tokay_function!("skip(n)", {
+    let n = count_operand(__function, &n)?;
+    let reader = &mut context.unwrap().runtime.reader;
+    let start = reader.tell();
+
+    for _ in 0..n {
+        if reader.next().is_none() {
+            break;
+        }
+    }
+
+    let range = reader.capture_from(&start);
+
+    if !range.is_empty() {
+        Ok(Accept::Push(Capture::Range(range, None, 5)))
+    } else {
+        Ok(Accept::Next) // Nothing to skip at end of input
+    }
+});
+
+tokay_function!("field(width)", {
+    let width = count_operand(__function, &width)?;
+    let reader = &mut context.unwrap().runtime.reader;
+    let start = reader.tell();
+
+    for _ in 0..width {
+        if reader.next().is_none() {
+            reader.reset(start);
+            return Err(Reject::Next);
+        }
+    }
+
+    Ok(Accept::Push(Capture::Range(
+        reader.capture_from(&start),
+        None,
+        5,
+    )))
+});
+
+tokay_function!("peek_str(s)", {
+    let reader = &mut context.unwrap().runtime.reader;
+    let start = reader.tell();
+    let mut ret = true;
+
+    for ch in s.to_string().chars() {
+        if reader.peek() != Some(ch) {
+            ret = false;
+            break;
+        }
+
+        reader.next();
+    }
+
+    // The input is only looked ahead, never consumed
+    reader.reset(start);
+    RefValue::from(ret).into()
+});
+
+tokay_function!("capture_range(n)", {
+    let context = context.unwrap();
+    let n = count_operand(__function, &n)?;
+
+    let range = if n == 0 {
+        // Capture 0 refers to the current span
+        Some(context.runtime.reader.capture_from(&context.reader_start))
+    } else {
+        // Captures beyond the stack, even beyond usize range, don't exist
+        let pos = context.capture_start.checked_add(n - 1);
+
+        match pos.and_then(|pos| context.runtime.stack.get(pos)) {
+            Some(Capture::Range(range, ..)) => Some(range.clone()),
+            _ => None,
+        }
+    };
+
+    match range {
+        Some(range) => value!([
+            (context.runtime.reader.absolute(range.start) as i64),
+            (context.runtime.reader.absolute(range.end) as i64)
+        ])
+        .into(),
+        None => value!(void).into(),
+    }
+});
+
+// Prints and returns the captures of the current context for debugging.
+// Every capture is described by a dict with its `kind`, which is one of `Empty`, `Range`,
+// `Value` or `Named` for aliased captures, its `severity` and its `value`.
+tokay_function!("dump_captures()", {
+    let context = context.unwrap();
+    let mut ret = value::List::new();
+
+    for (i, capture) in context.runtime.stack[context.capture_start..]
+        .iter()
+        .enumerate()
+    {
+        let (kind, value, alias) = match capture {
+            Capture::Empty => ("Empty", value!(void), None),
+            Capture::Range(range, alias, _) => (
+                "Range",
+                RefValue::from(context.runtime.reader.extract(range)),
+                alias.as_ref(),
+            ),
+            Capture::Value(value, alias, _) => ("Value", value.clone(), alias.as_ref()),
+        };
+
+        let mut descriptor = Dict::new();
+        descriptor.insert(
+            "kind".to_string(),
+            RefValue::from(if alias.is_some() { "Named" } else { kind }),
+        );
+        descriptor.insert(
+            "severity".to_string(),
+            RefValue::from(capture.get_severity() as i64),
+        );
+        descriptor.insert("value".to_string(), value);
+
+        if let Some(alias) = alias {
+            descriptor.insert("name".to_string(), RefValue::from(alias.to_string()));
+        }
+
+        let descriptor = RefValue::from(descriptor);
+        println!("${} {}", i + 1, descriptor.repr());
+        ret.push(descriptor);
+    }
+
+    RefValue::from(ret).into()
+});
+
+// Sets the severity of the capture on top of the stack.
+// Calls with a constant level are compiled into Op::SetSeverity; this serves any other calls.
+tokay_function!("severity(level)", {
+    let context = context.unwrap();
+    let level = match u8::try_from(int_operand(__function, &level)?) {
+        Ok(level) => level,
+        Err(_) => {
+            return Err(format!("{} level must be between 0 and 255", __function).into());
+        }
+    };
+
+    context.set_severity(level);
+    Ok(Accept::Next)
+});
+
 tokay_function!("repr(value)", value!(value.repr()).into());
+
+tokay_function!("to_json(value)", value!(value.to_json()).into());
+
+tokay_function!("is_empty(value)", {
+    let empty = match &*value.borrow() {
+        Value::Void | Value::Null => true,
+        Value::Str(s) => s.is_empty(),
+        Value::List(list) => list.is_empty(),
+        Value::Dict(dict) => dict.is_empty(),
+        _ => false,
+    };
+
+    RefValue::from(empty).into()
+});
+
+tokay_function!(
+    "is_finite(x)",
+    RefValue::from(x.to_f64().is_finite()).into()
+);
+tokay_function!("is_nan(x)", RefValue::from(x.to_f64().is_nan()).into());
+
+tokay_function!("str(value, void=void, null=void)", {
+    // void and null are rendered by the given placeholders, defaulting to "" and "null".
+    let ret = match &*value.borrow() {
+        Value::Void if !void.is_void() => void.to_string(),
+        Value::Null if !null.is_void() => null.to_string(),
+        value => value.to_string(),
+    };
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("clamp(x, lo, hi)", {
+    let is_float = [&x, &lo, &hi]
+        .iter()
+        .any(|value| matches!(&*value.borrow(), Value::Float(_)));
+
+    if is_float {
+        let (x, lo, hi) = (x.to_f64(), lo.to_f64(), hi.to_f64());
+
+        if lo > hi {
+            return Err(format!("{} expects lo <= hi, but {} > {}", __function, lo, hi).into());
+        }
+
+        RefValue::from(x.max(lo).min(hi)).into()
+    } else {
+        let (x, lo, hi) = (x.to_i64(), lo.to_i64(), hi.to_i64());
+
+        if lo > hi {
+            return Err(format!("{} expects lo <= hi, but {} > {}", __function, lo, hi).into());
+        }
+
+        RefValue::from(x.clamp(lo, hi)).into()
+    }
+});
+
+// Rounding of floats to integers; integers are passed through.
+
+// Rounds half away from zero, so round(2.5) is 3 and round(-2.5) is -3.
+tokay_function!("round(x)", {
+    let ret = match &*x.borrow() {
+        Value::Float(f) => f.round() as i64,
+        _ => x.to_i64(),
+    };
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("floor(x)", {
+    let ret = match &*x.borrow() {
+        Value::Float(f) => f.floor() as i64,
+        _ => x.to_i64(),
+    };
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("ceil(x)", {
+    let ret = match &*x.borrow() {
+        Value::Float(f) => f.ceil() as i64,
+        _ => x.to_i64(),
+    };
+
+    RefValue::from(ret).into()
+});
+
+// Uses floored division, so the remainder takes the sign of the divisor, like in Python.
+tokay_function!("divmod(a, b)", {
+    let is_float =
+        matches!(&*a.borrow(), Value::Float(_)) || matches!(&*b.borrow(), Value::Float(_));
+
+    if is_float {
+        let (a, b) = (a.to_f64(), b.to_f64());
+
+        if b == 0.0 {
+            return Err(format!("{} cannot divide by zero", __function).into());
+        }
+
+        let quotient = (a / b).floor();
+        value!([quotient, (a - quotient * b)]).into()
+    } else {
+        let (a, b) = (a.to_i64(), b.to_i64());
+
+        if b == 0 {
+            return Err(format!("{} cannot divide by zero", __function).into());
+        }
+
+        let (mut quotient, mut remainder) = match (a.checked_div(b), a.checked_rem(b)) {
+            (Some(quotient), Some(remainder)) => (quotient, remainder),
+            _ => return Err(format!("{} integer overflow", __function).into()),
+        };
+
+        if remainder != 0 && (remainder < 0) != (b < 0) {
+            quotient -= 1;
+            remainder += b;
+        }
+
+        value!([quotient, remainder]).into()
+    }
+});
+
+// Like the division operator, but returns `default` instead of failing on division by zero.
+tokay_function!("safe_div(a, b, default=void)", {
+    if b.to_f64() == 0.0 {
+        return default.into();
+    }
+
+    a.div(b)?.into()
+});
+
+// Retrieves an integer operand, like of the bitwise builtins.
+fn int_operand(function: &str, value: &RefValue) -> Result<i64, Reject> {
+    match &*value.borrow() {
+        Value::Integer(i) => Ok(*i),
+        _ => Err(format!("{} only accepts integers, not {}", function, value.repr()).into()),
+    }
+}
+
+// Retrieves a shift amount, which must not be negative.
+fn shift_operand(function: &str, value: &RefValue) -> Result<u32, Reject> {
+    match int_operand(function, value)? {
+        n if n < 0 => Err(format!("{} cannot shift by negative amount {}", function, n).into()),
+        n => Ok(n.min(64) as u32),
+    }
+}
+
+// Retrieves a count or width, which must be an integer of at least 0.
+fn count_operand(function: &str, value: &RefValue) -> Result<usize, Reject> {
+    match int_operand(function, value)? {
+        n if n < 0 => Err(format!("{} requires a count of at least 0, not {}", function, n).into()),
+        n => Ok(n as usize),
+    }
+}
+
+tokay_function!("band(a, b)", {
+    RefValue::from(int_operand(__function, &a)? & int_operand(__function, &b)?).into()
+});
+
+tokay_function!("bor(a, b)", {
+    RefValue::from(int_operand(__function, &a)? | int_operand(__function, &b)?).into()
+});
+
+tokay_function!("bxor(a, b)", {
+    RefValue::from(int_operand(__function, &a)? ^ int_operand(__function, &b)?).into()
+});
+
+tokay_function!(
+    "bnot(a)",
+    RefValue::from(!int_operand(__function, &a)?).into()
+);
+
+// Bits shifted out are discarded, so shifting by 64 or more results in 0.
+tokay_function!("shl(a, n)", {
+    let a = int_operand(__function, &a)?;
+    let n = shift_operand(__function, &n)?;
+
+    RefValue::from(a.checked_shl(n).unwrap_or(0)).into()
+});
+
+// Arithmetic shift keeping the sign, so shifting by 64 or more results in 0 or -1.
+tokay_function!("shr(a, n)", {
+    let a = int_operand(__function, &a)?;
+    let n = shift_operand(__function, &n)?;
+
+    RefValue::from(a >> n.min(63)).into()
+});
+
+// Integer result of gcd() and lcm(), promoted to float when it exceeds the int range.
+fn int_or_float(n: u128) -> RefValue {
+    match i64::try_from(n) {
+        Ok(n) => RefValue::from(n),
+        Err(_) => RefValue::from(n as f64),
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+// Greatest common divisor, which is always positive and gcd(0, 0) is 0.
+tokay_function!("gcd(a, b)", {
+    let a = int_operand(__function, &a)?.unsigned_abs();
+    let b = int_operand(__function, &b)?.unsigned_abs();
+
+    int_or_float(gcd(a, b) as u128).into()
+});
+
+// Least common multiple, which is always positive and lcm(0, n) is 0.
+// Results exceeding the int range are promoted to float.
+tokay_function!("lcm(a, b)", {
+    let a = int_operand(__function, &a)?.unsigned_abs();
+    let b = int_operand(__function, &b)?.unsigned_abs();
+
+    if a == 0 || b == 0 {
+        return RefValue::from(0).into();
+    }
+
+    int_or_float((a / gcd(a, b)) as u128 * b as u128).into()
+});
+
+tokay_function!("to_base(n, base)", {
+    let n = match &*n.borrow() {
+        Value::Integer(n) => *n,
+        _ => return Err(format!("{} only accepts integers, not {}", __function, n.repr()).into()),
+    };
+
+    let base = base.to_i64();
+    if !(2..=36).contains(&base) {
+        return Err(format!("{} requires a base from 2 to 36, not {}", __function, base).into());
+    }
+
+    let mut digits = Vec::new();
+    let mut rest = n.unsigned_abs();
+
+    loop {
+        digits.push(std::char::from_digit((rest % base as u64) as u32, base as u32).unwrap());
+        rest /= base as u64;
+
+        if rest == 0 {
+            break;
+        }
+    }
+
+    if n < 0 {
+        digits.push('-');
+    }
+
+    RefValue::from(digits.into_iter().rev().collect::<String>()).into()
+});
+
+// Retrieves a base of at least 2, defaulting to 10.
+fn digits_base(function: &str, base: &RefValue) -> Result<i64, Reject> {
+    if base.is_void() {
+        return Ok(10);
+    }
+
+    match base.to_i64() {
+        base if base >= 2 => Ok(base),
+        base => Err(format!("{} requires a base of at least 2, not {}", function, base).into()),
+    }
+}
+
+tokay_function!("digits(n, base=void)", {
+    let base = digits_base(__function, &base)?;
+    let mut n = match &*n.borrow() {
+        Value::Integer(n) if *n >= 0 => *n,
+        _ => {
+            return Err(format!(
+                "{} only accepts non-negative integers, not {}",
+                __function,
+                n.repr()
+            )
+            .into())
+        }
+    };
+
+    let mut digits = Vec::new();
+
+    loop {
+        digits.push(RefValue::from(n % base));
+        n /= base;
+
+        if n == 0 {
+            break;
+        }
+    }
+
+    let mut ret = value::List::new();
+    ret.extend(digits.into_iter().rev());
+    RefValue::from(ret).into()
+});
+
+tokay_function!("from_digits(list, base=void)", {
+    let base = digits_base(__function, &base)?;
+    let mut n: i64 = 0;
+
+    for digit in value::List::from(list).into_iter() {
+        let digit = match &*digit.borrow() {
+            Value::Integer(digit) if (0..base).contains(digit) => *digit,
+            _ => {
+                return Err(format!(
+                    "{} requires digits from 0 to {}, not {}",
+                    __function,
+                    base - 1,
+                    digit.repr()
+                )
+                .into())
+            }
+        };
+
+        n = n
+            .checked_mul(base)
+            .and_then(|n| n.checked_add(digit))
+            .ok_or_else(|| format!("{} overflows", __function))?;
+    }
+
+    RefValue::from(n).into()
+});
+
+tokay_function!("chars(s)", {
+    let mut list = value::List::new();
+
+    for ch in s.to_string().chars() {
+        list.push(RefValue::from(ch.to_string()));
+    }
+
+    RefValue::from(list).into()
+});
+
+tokay_function!("from_chars(list)", {
+    let list = value::List::from(list);
+    let mut ret = String::new();
+
+    for item in list.iter() {
+        ret.push_str(&item.to_string());
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("strip_prefix(s, prefix)", {
+    let s = s.to_string();
+
+    match s.strip_prefix(&prefix.to_string()) {
+        Some(rest) => RefValue::from(rest),
+        None => RefValue::from(s),
+    }
+    .into()
+});
+
+tokay_function!("strip_suffix(s, suffix)", {
+    let s = s.to_string();
+
+    match s.strip_suffix(&suffix.to_string()) {
+        Some(rest) => RefValue::from(rest),
+        None => RefValue::from(s),
+    }
+    .into()
+});
+
+// Retrieves the single character of a string, used by count_leading() and count_trailing().
+fn single_char(function: &str, ch: &RefValue) -> Result<char, Reject> {
+    let s = ch.to_string();
+    let mut chars = s.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok(ch),
+        _ => Err(format!(
+            "{} requires a single character, not {}",
+            function,
+            ch.repr()
+        )
+        .into()),
+    }
+}
+
+tokay_function!("count_leading(s, ch)", {
+    let ch = single_char(__function, &ch)?;
+    let count = s.to_string().chars().take_while(|c| *c == ch).count();
+    RefValue::from(count as i64).into()
+});
+
+tokay_function!("count_trailing(s, ch)", {
+    let ch = single_char(__function, &ch)?;
+    let count = s.to_string().chars().rev().take_while(|c| *c == ch).count();
+    RefValue::from(count as i64).into()
+});
+
+// Splits an identifier-like string into words at underscores and case boundaries.
+fn split_case_words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch == '_' || ch == '-' || ch.is_whitespace() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+
+            continue;
+        }
+
+        // Split before an uppercase letter following a lowercase letter or digit ("parseHTML"),
+        // or ending an acronym followed by a lowercase letter ("HTMLString").
+        if ch.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+
+            if !prev.is_uppercase() || next_is_lower {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+
+        word.push(ch);
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+// Capitalizes a word, lowercasing its remaining characters.
+fn capitalize_word(word: &str) -> String {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+tokay_function!("to_snake(s)", {
+    let words: Vec<String> = split_case_words(&s.to_string())
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect();
+
+    RefValue::from(words.join("_")).into()
+});
+
+tokay_function!("to_camel(s)", {
+    let mut ret = String::new();
+
+    for (i, word) in split_case_words(&s.to_string()).iter().enumerate() {
+        if i == 0 {
+            ret.push_str(&word.to_lowercase());
+        } else {
+            ret.push_str(&capitalize_word(word));
+        }
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("to_pascal(s)", {
+    let mut ret = String::new();
+
+    for word in split_case_words(&s.to_string()) {
+        ret.push_str(&capitalize_word(&word));
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("indent(s, tab_width=void)", {
+    let tab_width = if tab_width.is_void() {
+        8
+    } else {
+        count_operand(__function, &tab_width)?.max(1)
+    };
+
+    let mut columns = 0;
+
+    for ch in s.to_string().chars() {
+        match ch {
+            ' ' => columns += 1,
+            '\t' => columns += tab_width - columns % tab_width,
+            _ => break,
+        }
+    }
+
+    RefValue::from(columns as i64).into()
+});
+
+tokay_function!("expand_tabs(s, tab_width=void)", {
+    let tab_width = if tab_width.is_void() {
+        8
+    } else {
+        count_operand(__function, &tab_width)?.max(1)
+    };
+
+    let mut ret = String::new();
+    let mut column = 0;
+
+    for ch in s.to_string().chars() {
+        match ch {
+            '\t' => {
+                let spaces = tab_width - column % tab_width;
+                ret.push_str(&" ".repeat(spaces));
+                column += spaces;
+            }
+            '\n' | '\r' => {
+                ret.push(ch);
+                column = 0;
+            }
+            ch => {
+                ret.push(ch);
+                column += 1;
+            }
+        }
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("unexpand(s, tab_width=void)", {
+    let tab_width = if tab_width.is_void() {
+        8
+    } else {
+        count_operand(__function, &tab_width)?.max(1)
+    };
+
+    let s = s.to_string();
+    let mut ret = String::with_capacity(s.len());
+
+    for line in s.split_inclusive('\n') {
+        let mut columns = 0;
+        let mut rest = line;
+
+        // Only leading whitespace is converted
+        for (i, ch) in line.char_indices() {
+            match ch {
+                ' ' => columns += 1,
+                '\t' => columns += tab_width - columns % tab_width,
+                _ => {
+                    rest = &line[i..];
+                    break;
+                }
+            }
+
+            rest = &line[i + 1..];
+        }
+
+        ret.push_str(&"\t".repeat(columns / tab_width));
+        ret.push_str(&" ".repeat(columns % tab_width));
+        ret.push_str(rest);
+    }
+
+    RefValue::from(ret).into()
+});
+
+// Removes the leading whitespace common to all non-blank lines, blank lines are emptied.
+tokay_function!("dedent(s)", {
+    let s = s.to_string();
+
+    let is_blank = |line: &str| line.trim().is_empty();
+    let leading = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+
+    // Find the longest whitespace prefix shared by all non-blank lines
+    let mut prefix: Option<&str> = None;
+
+    for line in s.lines().filter(|line| !is_blank(line)) {
+        let indent = &line[..leading(line)];
+
+        prefix = Some(match prefix {
+            None => indent,
+            Some(prefix) => {
+                let common = prefix
+                    .bytes()
+                    .zip(indent.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+
+                &prefix[..common]
+            }
+        });
+    }
+
+    let prefix = prefix.unwrap_or("");
+    let mut ret = String::with_capacity(s.len());
+
+    for line in s.split_inclusive('\n') {
+        if is_blank(line) {
+            ret.push_str(&line[line.trim_end_matches(['\r', '\n']).len()..]);
+        } else {
+            ret.push_str(&line[prefix.len()..]);
+        }
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("normalize_newlines(s, to=void)", {
+    let to = if to.is_void() {
+        "\n".to_string()
+    } else {
+        to.to_string()
+    };
+
+    let s = s.to_string();
+    let mut ret = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                ret.push_str(&to);
+            }
+            '\n' => ret.push_str(&to),
+            ch => ret.push(ch),
+        }
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("quote(s, style=void)", {
+    let quote = if style.is_void() {
+        '"'
+    } else {
+        match style.to_string().as_str() {
+            "\"" => '"',
+            "'" => '\'',
+            style => {
+                return Err(format!(
+                    "{} expects style to be either '\"' or \"'\", not {:?}",
+                    __function, style
+                )
+                .into())
+            }
+        }
+    };
+
+    let mut ret = String::new();
+    ret.push(quote);
+
+    for ch in s.to_string().chars() {
+        match ch {
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            ch if ch == quote => {
+                ret.push('\\');
+                ret.push(ch);
+            }
+            ch => ret.push(ch),
+        }
+    }
+
+    ret.push(quote);
+    RefValue::from(ret).into()
+});
+
+tokay_function!("wrap(s, width, break_long=true)", {
+    let width = count_operand(__function, &width)?.max(1);
+    let break_long = break_long.is_true();
+
+    let mut lines = value::List::new();
+    let mut line = String::new();
+
+    for word in s.to_string().split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+
+        // Hard-break words exceeding the line width
+        while break_long && word.len() > width {
+            if !line.is_empty() {
+                lines.push(RefValue::from(std::mem::take(&mut line)));
+            }
+
+            lines.push(RefValue::from(word.drain(..width).collect::<String>()));
+        }
+
+        if word.is_empty() {
+            continue;
+        }
+
+        if !line.is_empty() && line.chars().count() + 1 + word.len() > width {
+            lines.push(RefValue::from(std::mem::take(&mut line)));
+        }
+
+        if !line.is_empty() {
+            line.push(' ');
+        }
+
+        line.extend(word);
+    }
+
+    if !line.is_empty() {
+        lines.push(RefValue::from(line));
+    }
+
+    RefValue::from(lines).into()
+});
+
+// CRC-32 checksum (IEEE 802.3) over the UTF-8 bytes of a string.
+tokay_function!("crc32(s)", {
+    let mut crc = 0xffffffffu32;
+
+    for byte in s.to_string().bytes() {
+        crc ^= byte as u32;
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    RefValue::from(!crc as i64).into()
+});
+
+// Adler-32 checksum over the UTF-8 bytes of a string.
+tokay_function!("adler32(s)", {
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for byte in s.to_string().bytes() {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    RefValue::from(((b << 16) | a) as i64).into()
+});
+
+// Set operations on lists, returning deduplicated lists in first-seen order.
+
+tokay_function!("set_difference(a, b)", {
+    let b = value::List::from(b);
+    let mut ret = value::List::new();
+
+    for item in value::List::from(a).into_iter() {
+        if !b.contains(&item) && !ret.contains(&item) {
+            ret.push(item);
+        }
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("set_intersection(a, b)", {
+    let b = value::List::from(b);
+    let mut ret = value::List::new();
+
+    for item in value::List::from(a).into_iter() {
+        if b.contains(&item) && !ret.contains(&item) {
+            ret.push(item);
+        }
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("set_union(a, b)", {
+    let mut ret = value::List::new();
+
+    for item in value::List::from(a)
+        .into_iter()
+        .chain(value::List::from(b).into_iter())
+    {
+        if !ret.contains(&item) {
+            ret.push(item);
+        }
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("partition(list, func)", {
+    let context = context.unwrap();
+    let mut matching = value::List::new();
+    let mut non_matching = value::List::new();
+
+    for item in value::List::from(list).into_iter() {
+        if context.call(&func, vec![item.clone()])?.is_true() {
+            matching.push(item);
+        } else {
+            non_matching.push(item);
+        }
+    }
+
+    value!([(RefValue::from(matching)), (RefValue::from(non_matching))]).into()
+});
+
+tokay_function!("rle(list)", {
+    let mut runs: Vec<(RefValue, i64)> = Vec::new();
+
+    for item in value::List::from(list).into_iter() {
+        match runs.last_mut() {
+            Some((value, count)) if *value == item => *count += 1,
+            _ => runs.push((item, 1)),
+        }
+    }
+
+    let mut ret = value::List::new();
+
+    for (value, count) in runs {
+        ret.push(value!([value, count]));
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("rle_decode(pairs)", {
+    let mut ret = value::List::new();
+
+    for pair in value::List::from(pairs).into_iter() {
+        let (value, count) = match pair.borrow().list() {
+            Some(list) if list.len() == 2 => (list[0].clone(), list[1].to_i64()),
+            _ => {
+                return Err(format!(
+                    "{} expects pairs of value and count, not {}",
+                    __function,
+                    pair.repr()
+                )
+                .into())
+            }
+        };
+
+        for _ in 0..count {
+            ret.push(value.clone());
+        }
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("merge_strings(list)", {
+    let mut ret = value::List::new();
+    let mut pending: Option<String> = None;
+
+    for item in value::List::from(list).into_iter() {
+        if let Value::Str(s) = &*item.borrow() {
+            pending.get_or_insert_with(String::new).push_str(s.as_str());
+            continue;
+        }
+
+        if let Some(s) = pending.take() {
+            ret.push(RefValue::from(s));
+        }
+
+        ret.push(item);
+    }
+
+    if let Some(s) = pending {
+        ret.push(RefValue::from(s));
+    }
+
+    RefValue::from(ret).into()
+});
+
+// Rotates left by n, a negative n rotates right.
+tokay_function!("rotate(list, n)", {
+    let mut list = value::List::from(list);
+
+    if !list.is_empty() {
+        let n = n.to_i64().rem_euclid(list.len() as i64) as usize;
+        list.rotate_left(n);
+    }
+
+    RefValue::from(list).into()
+});
+
+// Draws a random index below `bound` from the runtime's pseudo-random number generator.
+#[cfg(feature = "random")]
+fn random_index(_function: &str, context: &mut Context, bound: usize) -> Result<usize, Reject> {
+    Ok(context.runtime.random(bound))
+}
+
+#[cfg(not(feature = "random"))]
+fn random_index(function: &str, _context: &mut Context, _bound: usize) -> Result<usize, Reject> {
+    Err(format!("{} requires the 'random' feature", function).into())
+}
+
+// Returns a randomly permuted copy of list.
+tokay_function!("shuffle(list)", {
+    let context = context.unwrap();
+    let mut list = value::List::from(list);
+
+    for i in (1..list.len()).rev() {
+        let j = random_index(__function, context, i + 1)?;
+        list.swap(i, j);
+    }
+
+    RefValue::from(list).into()
+});
+
+// Returns k randomly chosen items of list without replacement.
+tokay_function!("sample(list, k)", {
+    let context = context.unwrap();
+    let mut list = value::List::from(list);
+    let k = k.to_i64();
+
+    if k < 0 || k as usize > list.len() {
+        return Err(format!(
+            "{} cannot take {} items from a list of {}",
+            __function,
+            k,
+            list.len()
+        )
+        .into());
+    }
+
+    let k = k as usize;
+
+    for i in 0..k {
+        let j = i + random_index(__function, context, list.len() - i)?;
+        list.swap(i, j);
+    }
+
+    list.truncate(k);
+    RefValue::from(list).into()
+});
+
+// Finds the index of the first item winning all comparisons against the items before it.
+fn arg_best(list: RefValue, better: fn(&RefValue, &RefValue) -> bool) -> RefValue {
+    let mut best: Option<(usize, RefValue)> = None;
+
+    for (index, item) in value::List::from(list).into_iter().enumerate() {
+        if best.as_ref().is_none_or(|(_, value)| better(&item, value)) {
+            best = Some((index, item));
+        }
+    }
+
+    match best {
+        Some((index, _)) => RefValue::from(index as i64),
+        None => value!(void),
+    }
+}
+
+tokay_function!("argmin(list)", arg_best(list, |a, b| a < b).into());
+
+tokay_function!("argmax(list)", arg_best(list, |a, b| a > b).into());
+
+tokay_function!("transpose(list, fill=void)", {
+    let rows: Vec<value::List> = value::List::from(list)
+        .into_iter()
+        .map(value::List::from)
+        .collect();
+
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+
+    if fill.is_void() && rows.iter().any(|row| row.len() != width) {
+        return Err(format!(
+            "{} requires rows of equal length, or a fill value",
+            __function
+        )
+        .into());
+    }
+
+    let mut ret = value::List::new();
+
+    for column in 0..width {
+        let mut items = value::List::new();
+
+        for row in rows.iter() {
+            items.push(row.get(column).cloned().unwrap_or_else(|| fill.clone()));
+        }
+
+        ret.push(RefValue::from(items));
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("interleave(*args, **nargs)", {
+    let mut nargs = nargs.unwrap_or_else(Dict::new);
+    let pad = nargs.remove("pad").is_some_and(|pad| pad.is_true());
+
+    if let Some((name, _)) = nargs.iter().next() {
+        return Err(format!("{} doesn't accept parameter '{}'", __function, name).into());
+    }
+
+    let lists: Vec<value::List> = args.into_iter().map(value::List::from).collect();
+    let lengths = lists.iter().map(|list| list.len());
+
+    // Without padding, stop when the shortest list is exhausted
+    let len = if pad { lengths.max() } else { lengths.min() }.unwrap_or(0);
+    let mut ret = value::List::new();
+
+    for i in 0..len {
+        for list in lists.iter() {
+            ret.push(list.get(i).cloned().unwrap_or_else(|| value!(void)));
+        }
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("product_of(*args, **nargs)", {
+    let mut nargs = nargs.unwrap_or_else(Dict::new);
+    let max = nargs.remove("max").map_or(10_000, |max| max.to_usize());
+
+    if let Some((name, _)) = nargs.iter().next() {
+        return Err(format!("{} doesn't accept parameter '{}'", __function, name).into());
+    }
+
+    let lists: Vec<value::List> = args.into_iter().map(value::List::from).collect();
+
+    // Refuse to build products exceeding the maximum size
+    let size = lists
+        .iter()
+        .try_fold(1usize, |size, list| size.checked_mul(list.len()));
+
+    if size.is_none_or(|size| size > max) {
+        return Err(format!("{} exceeds the maximum of {} combinations", __function, max).into());
+    }
+
+    let mut combinations = vec![Vec::new()];
+
+    for list in lists.iter() {
+        let mut next = Vec::new();
+
+        for combination in combinations {
+            for item in list.iter() {
+                let mut combination: Vec<RefValue> = combination.clone();
+                combination.push(item.clone());
+                next.push(combination);
+            }
+        }
+
+        combinations = next;
+    }
+
+    let mut ret = value::List::new();
+
+    for combination in combinations {
+        let mut items = value::List::new();
+
+        for item in combination {
+            items.push(item);
+        }
+
+        ret.push(RefValue::from(items));
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("chunk_by(list, func)", {
+    let context = context.unwrap();
+    let mut ret = value::List::new();
+    let mut chunk = value::List::new();
+    let mut last_key = None;
+
+    for item in value::List::from(list).into_iter() {
+        let key = context.call(&func, vec![item.clone()])?;
+
+        if last_key.is_some_and(|last_key| last_key != key) {
+            ret.push(RefValue::from(chunk));
+            chunk = value::List::new();
+        }
+
+        chunk.push(item);
+        last_key = Some(key);
+    }
+
+    if !chunk.is_empty() {
+        ret.push(RefValue::from(chunk));
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("drop_while(list, func)", {
+    let context = context.unwrap();
+    let mut ret = value::List::new();
+    let mut dropping = true;
+
+    for item in value::List::from(list).into_iter() {
+        if dropping && context.call(&func, vec![item.clone()])?.is_true() {
+            continue;
+        }
+
+        dropping = false;
+        ret.push(item);
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("take_while(list, func)", {
+    let context = context.unwrap();
+    let mut ret = value::List::new();
+
+    for item in value::List::from(list).into_iter() {
+        if !context.call(&func, vec![item.clone()])?.is_true() {
+            break;
+        }
+
+        ret.push(item);
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("find(list, func)", {
+    let context = context.unwrap();
+
+    for item in value::List::from(list).into_iter() {
+        if context.call(&func, vec![item.clone()])?.is_true() {
+            return item.into();
+        }
+    }
+
+    value!(void).into()
+});
+
+tokay_function!("find_index(list, func)", {
+    let context = context.unwrap();
+
+    for (i, item) in value::List::from(list).into_iter().enumerate() {
+        if context.call(&func, vec![item])?.is_true() {
+            return RefValue::from(i as i64).into();
+        }
+    }
+
+    value!(void).into()
+});
+
+tokay_function!("all(list, func=void)", {
+    let mut context = context;
+
+    for item in value::List::from(list).into_iter() {
+        let res = if func.is_void() {
+            item
+        } else {
+            context.as_mut().unwrap().call(&func, vec![item])?
+        };
+
+        if !res.is_true() {
+            return value!(false).into();
+        }
+    }
+
+    value!(true).into()
+});
+
+tokay_function!("any(list, func=void)", {
+    let mut context = context;
+
+    for item in value::List::from(list).into_iter() {
+        let res = if func.is_void() {
+            item
+        } else {
+            context.as_mut().unwrap().call(&func, vec![item])?
+        };
+
+        if res.is_true() {
+            return value!(true).into();
+        }
+    }
+
+    value!(false).into()
+});
+
+tokay_function!("repeat_str(func, n, sep=void)", {
+    let context = context.unwrap();
+    let sep = if sep.is_void() {
+        String::new()
+    } else {
+        sep.to_string()
+    };
+
+    let n = count_operand(__function, &n)?;
+    let mut parts = Vec::new();
+
+    for _ in 0..n {
+        parts.push(context.call(&func, Vec::new())?.to_string());
+    }
+
+    RefValue::from(parts.join(&sep)).into()
+});
+
+// Collects the keys of a list not present in a dict, used by has_keys() and missing_keys().
+fn collect_missing_keys(
+    function: &str,
+    dict: &RefValue,
+    keys: RefValue,
+) -> Result<value::List, String> {
+    if let Value::Dict(dict) = &*dict.borrow() {
+        let mut missing = value::List::new();
+
+        for key in value::List::from(keys).into_iter() {
+            if !dict.contains_key(&key.to_string()) {
+                missing.push(key);
+            }
+        }
+
+        Ok(missing)
+    } else {
+        Err(format!(
+            "{} only accepts 'dict' as first parameter, not '{}'",
+            function,
+            dict.borrow().name()
+        ))
+    }
+}
+
+// Scopes are dicts holding a dict of `symbols` and an optional `parent` scope.
+fn scope_symbols(function: &str, scope: &RefValue) -> Result<RefValue, Reject> {
+    if let Some(symbols) = scope.borrow().dict().and_then(|scope| scope.get("symbols")) {
+        if symbols.borrow().dict().is_some() {
+            return Ok(symbols.clone());
+        }
+    }
+
+    Err(format!("{} expects a scope, not {}", function, scope.repr()).into())
+}
+
+tokay_function!("scope_new(parent=void)", {
+    let mut scope = Dict::new();
+    scope.insert("symbols".to_string(), RefValue::from(Dict::new()));
+
+    if !parent.is_void() {
+        scope_symbols(__function, &parent)?;
+        scope.insert("parent".to_string(), parent);
+    }
+
+    RefValue::from(scope).into()
+});
+
+tokay_function!("scope_define(scope, name, value)", {
+    let symbols = scope_symbols(__function, &scope)?;
+
+    if let Value::Dict(symbols) = &mut *symbols.borrow_mut() {
+        symbols.insert(name.to_string(), value);
+    }
+
+    value!(void).into()
+});
+
+tokay_function!("scope_lookup(scope, name)", {
+    let name = name.to_string();
+    let mut scope = scope;
+
+    // Walk up the parent chain until the name is defined
+    loop {
+        if let Some(value) = scope_symbols(__function, &scope)?
+            .borrow()
+            .dict()
+            .unwrap()
+            .get(&name)
+        {
+            return value.clone().into();
+        }
+
+        let parent = scope.borrow().dict().unwrap().get("parent").cloned();
+
+        match parent {
+            Some(parent) => scope = parent,
+            None => return value!(void).into(),
+        }
+    }
+});
+
+tokay_function!("has_keys(dict, keys)", {
+    RefValue::from(collect_missing_keys(__function, &dict, keys)?.is_empty()).into()
+});
+
+tokay_function!("missing_keys(dict, keys)", {
+    RefValue::from(collect_missing_keys(__function, &dict, keys)?).into()
+});
+
+tokay_function!("pipe(value, *args)", {
+    let context = context.unwrap();
+    let mut value = value;
+
+    for (i, func) in args.into_iter().enumerate() {
+        value = match context.call(&func, vec![value]) {
+            Err(Reject::Error(mut err)) => {
+                err.message = format!("{} failed at stage {}: {}", __function, i, err.message);
+                return Err(Reject::Error(err));
+            }
+            res => res?,
+        };
+    }
+
+    value.into()
+});
+
+// Calls func with the items of list as positional arguments.
+tokay_function!("apply(func, list)", {
+    let context = context.unwrap();
+    let args = value::List::from(list);
+
+    context.call(&func, args.into_iter().collect())?.into()
+});
+
+tokay_function!("scan_all(func)", {
+    let context = context.unwrap();
+    let mut results = value::List::new();
+
+    while !context.runtime.reader.eof() {
+        let start = context.runtime.reader.tell();
+
+        match context.call(&func, Vec::new()) {
+            Ok(value) => {
+                // Stop on first result not consuming any input
+                if context.runtime.reader.tell().offset == start.offset {
+                    break;
+                }
+
+                if !value.is_void() {
+                    results.push(value);
+                }
+            }
+            Err(reject @ (Reject::Main | Reject::Error(_))) => return Err(reject),
+            Err(_) => {
+                context.runtime.reader.reset(start);
+                break;
+            }
+        }
+    }
+
+    RefValue::from(results).into()
+});
+
+tokay_function!("find_all(parser)", {
+    let context = context.unwrap();
+    let mut results = value::List::new();
+
+    while !context.runtime.reader.eof() {
+        let start = context.runtime.reader.tell();
+
+        match context.call(&parser, Vec::new()) {
+            Ok(value) if context.runtime.reader.tell().offset > start.offset => {
+                if !value.is_void() {
+                    results.push(value);
+                }
+
+                continue;
+            }
+            Ok(_) => {}
+            Err(reject @ (Reject::Main | Reject::Error(_))) => return Err(reject),
+            Err(_) => context.runtime.reader.reset(start),
+        }
+
+        // Skip a character of input not matched by the parser
+        context.runtime.reader.next();
+    }
+
+    RefValue::from(results).into()
+});
+
+// Like the `?` modifier, but tells whether the parser matched.
+tokay_function!("optional(parser)", {
+    let context = context.unwrap();
+    let start = context.runtime.reader.tell();
+
+    let matched = match context.call(&parser, Vec::new()) {
+        Ok(_) => true,
+        Err(reject @ (Reject::Main | Reject::Error(_))) => return Err(reject),
+        Err(_) => {
+            context.runtime.reader.reset(start);
+            false
+        }
+    };
+
+    RefValue::from(matched).into()
+});
+
+// Like the `peek` keyword, matches the parser and returns its result without consuming input.
+// Named lookahead() because `peek` is a reserved keyword and can't be used as a builtin name.
+tokay_function!("lookahead(parser)", {
+    let context = context.unwrap();
+    let start = context.runtime.reader.tell();
+
+    let value = context.call(&parser, Vec::new());
+    context.runtime.reader.reset(start);
+
+    match value {
+        Ok(value) => value.into(),
+        Err(reject @ (Reject::Main | Reject::Error(_))) => Err(reject),
+        Err(_) => Err(Reject::Next),
+    }
+});
+
+// Like the `not` keyword, rejects when the parser matches, without consuming input.
+// Named lookahead_not() because `not` is a reserved keyword as well.
+tokay_function!("lookahead_not(parser)", {
+    let context = context.unwrap();
+    let start = context.runtime.reader.tell();
+
+    let result = context.call(&parser, Vec::new());
+    context.runtime.reader.reset(start);
+
+    match result {
+        Ok(_) => Err(Reject::Next),
+        Err(reject @ (Reject::Main | Reject::Error(_))) => Err(reject),
+        Err(_) => Ok(Accept::Next),
+    }
+});
+
+// Deep copy of nested lists and dicts, used by set_path().
+fn deep_copy(value: &RefValue) -> RefValue {
+    match &*value.borrow() {
+        Value::List(list) => {
+            let mut ret = value::List::new();
+
+            for item in list.iter() {
+                ret.push(deep_copy(item));
+            }
+
+            RefValue::from(ret)
+        }
+        Value::Dict(dict) => {
+            let mut ret = Dict::new();
+
+            for (key, item) in dict.iter() {
+                ret.insert(key.clone(), deep_copy(item));
+            }
+
+            RefValue::from(ret)
+        }
+        value => value.clone().into(),
+    }
+}
+
+tokay_function!("get_path(value, path)", {
+    let mut value = value;
+
+    for step in value::List::from(path).into_iter() {
+        let next = match &*value.borrow() {
+            Value::List(list) => match &*step.borrow() {
+                Value::Integer(index) if *index >= 0 => list.get(*index as usize).cloned(),
+                _ => None,
+            },
+            Value::Dict(dict) => dict.get(&step.to_string()).cloned(),
+            _ => None,
+        };
+
+        if let Some(next) = next {
+            value = next;
+        } else {
+            return value!(void).into();
+        }
+    }
+
+    value.into()
+});
+
+tokay_function!("set_path(value, path, new)", {
+    let path = value::List::from(path);
+
+    if path.is_empty() {
+        return new.into();
+    }
+
+    let ret = deep_copy(&value);
+    let mut value = ret.clone();
+
+    for (i, step) in path.iter().enumerate() {
+        let last = i + 1 == path.len();
+
+        let next = match &mut *value.borrow_mut() {
+            Value::List(list) => {
+                let index = match &*step.borrow() {
+                    Value::Integer(index) if *index >= 0 && (*index as usize) < list.len() => {
+                        *index as usize
+                    }
+                    _ => {
+                        return Err(
+                            format!("{} cannot index list by {}", __function, step.repr()).into(),
+                        )
+                    }
+                };
+
+                if last {
+                    list[index] = new.clone();
+                }
+
+                list[index].clone()
+            }
+            Value::Dict(dict) => {
+                let key = step.to_string();
+
+                if last {
+                    dict.insert(key.clone(), new.clone());
+                }
+
+                if let Some(next) = dict.get(&key) {
+                    next.clone()
+                } else {
+                    return Err(format!("{} has no key {}", __function, step.repr()).into());
+                }
+            }
+            other => {
+                return Err(format!("{} cannot descend into '{}'", __function, other.name()).into())
+            }
+        };
+
+        value = next;
+    }
+
+    ret.into()
+});
@@ -1,4 +1,5 @@
 //! Compiler's internal Abstract Syntax Tree traversal
+use std::cmp::Ordering;
 use std::collections::HashSet;
 
 use ::macros::tokay_function;
@@ -25,6 +26,54 @@ fn identifier_is_valid(ident: &str) -> Result<(), Error> {
     }
 }
 
+/** Retrieves the level of a call to the severity() builtin with a constant integer level.
+
+Such calls are compiled into Op::SetSeverity instead of calling the builtin. */
+fn traverse_severity_level(compiler: &mut Compiler, children: &List) -> Option<u8> {
+    fn get_str(node: &Dict, key: &str) -> Option<String> {
+        node.get(key)
+            .and_then(|value| value.borrow().str().map(str::to_string))
+    }
+
+    if children.len() != 2 {
+        return None;
+    }
+
+    let callee = children[0].borrow();
+    let callee = callee.dict()?;
+
+    if get_str(callee, "emit")? != "identifier" || get_str(callee, "value")? != "severity" {
+        return None;
+    }
+
+    let params = List::from(&*children[1].borrow());
+
+    if params.len() != 1 {
+        return None;
+    }
+
+    let param = params[0].borrow();
+    let param = param.dict()?;
+
+    if get_str(param, "emit")? != "param" {
+        return None;
+    }
+
+    let level = param.get("children")?.borrow();
+    let level = level.dict()?;
+
+    if get_str(level, "emit")? != "value_integer" {
+        return None;
+    }
+
+    // Only when severity isn't redefined
+    if !compiler.get_constant("severity")?.is_builtin("severity") {
+        return None;
+    }
+
+    u8::try_from(level.get("value")?.to_i64()).ok()
+}
+
 /// AST traversal entry
 pub(super) fn traverse(compiler: &mut Compiler, ast: &RefValue) {
     traverse_node_or_list(compiler, ast);
@@ -580,6 +629,10 @@ fn traverse_node(compiler: &mut Compiler, node: &Dict) -> ImlResult {
             let children = node["children"].borrow();
             let children = List::from(&*children);
 
+            if let Some(level) = traverse_severity_level(compiler, &children) {
+                return ImlResult::Ops(vec![Op::SetSeverity(level).into()]);
+            }
+
             let mut ops = Vec::new();
             let mut args = 0;
             let mut nargs = 0;
@@ -832,6 +885,10 @@ fn traverse_node(compiler: &mut Compiler, node: &Dict) -> ImlResult {
 
                 let body = traverse_node_or_list(compiler, children).into_ops(compiler, true);
 
+                // Named parselets must remain in the statics even when unreferenced,
+                // as lookup() and Program::with_main() resolve them by name at runtime.
+                compiler.define_parselet_constants();
+
                 let main = compiler.pop_parselet(
                     Some("__main__".to_string()),
                     Vec::new(),
@@ -922,6 +979,7 @@ fn traverse_node(compiler: &mut Compiler, node: &Dict) -> ImlResult {
                             "sub" => left.sub(right),
                             "mul" => left.mul(right),
                             "div" => left.div(right),
+                            "mod" => left.rem(right),
                             _ => {
                                 unimplemented!("op_binary_{}", parts[2]);
                             }
@@ -942,6 +1000,7 @@ fn traverse_node(compiler: &mut Compiler, node: &Dict) -> ImlResult {
                         "sub" => Op::Sub.into(),
                         "mul" => Op::Mul.into(),
                         "div" => Op::Div.into(),
+                        "mod" => Op::Mod.into(),
                         _ => {
                             unimplemented!("op_binary_{}", parts[2]);
                         }
@@ -992,13 +1051,19 @@ fn traverse_node(compiler: &mut Compiler, node: &Dict) -> ImlResult {
                     if let (Ok(left), Ok(right)) =
                         (left.get_evaluable_value(), right.get_evaluable_value())
                     {
+                        let ordering = left.compare(&right);
+
                         return ImlResult::Value(ImlValue::Value(RefValue::from(match parts[2] {
-                            "equal" => left == right,
-                            "unequal" => left != right,
-                            "lowerequal" => left <= right,
-                            "greaterequal" => left >= right,
-                            "lower" => left < right,
-                            "greater" => left > right,
+                            "equal" => ordering == Some(Ordering::Equal),
+                            "unequal" => ordering != Some(Ordering::Equal),
+                            "lowerequal" => {
+                                matches!(ordering, Some(Ordering::Less | Ordering::Equal))
+                            }
+                            "greaterequal" => {
+                                matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+                            }
+                            "lower" => ordering == Some(Ordering::Less),
+                            "greater" => ordering == Some(Ordering::Greater),
                             "and" => left.is_true() && right.is_true(),
                             "or" => left.is_true() || right.is_true(),
                             _ => {
@@ -1271,7 +1336,11 @@ fn traverse_node(compiler: &mut Compiler, node: &Dict) -> ImlResult {
             if ops.len() == 1 {
                 ImlResult::Ops(ops)
             } else if ops.len() > 0 {
-                ImlResult::Ops(vec![ImlSequence::new(ops)])
+                ImlResult::Ops(vec![if compiler.verbose_sequences {
+                    ImlSequence::new_verbose(ops)
+                } else {
+                    ImlSequence::new(ops)
+                }])
             } else {
                 ImlResult::Empty
             }
@@ -1364,7 +1433,7 @@ tokay_function!("ast(emit, value=void)", {
 
     let value = if value.is_void() {
         context
-            .collect(context.capture_start, false, true, false, 0)
+            .collect(context.capture_start, false, true, false, 0, false)
             .unwrap_or(None)
     } else {
         Some(value)
@@ -1398,3 +1467,492 @@ tokay_function!("ast_print(ast)", {
     print(&ast);
     value!(void).into()
 });
+
+/// Calls a function on every AST node of a tree, in pre-order.
+fn visit_nodes(node: &RefValue, func: &mut dyn FnMut(&Dict)) {
+    let node = node.borrow();
+
+    if let Some(list) = node.list() {
+        for item in list.iter() {
+            visit_nodes(item, func);
+        }
+    } else if let Some(dict) = node.dict() {
+        if dict.contains_key("emit") {
+            func(dict);
+
+            if let Some(children) = dict.get("children") {
+                visit_nodes(children, func);
+            }
+        }
+    }
+}
+
+tokay_function!("emit_types(node)", {
+    let mut emits = std::collections::BTreeSet::new();
+
+    visit_nodes(&node, &mut |dict| {
+        emits.insert(dict["emit"].to_string());
+    });
+
+    let mut ret = List::new();
+
+    for emit in emits {
+        ret.push(RefValue::from(emit));
+    }
+
+    RefValue::from(ret).into()
+});
+
+tokay_function!("node_counts(node)", {
+    let mut counts = std::collections::BTreeMap::<String, i64>::new();
+
+    visit_nodes(&node, &mut |dict| {
+        *counts.entry(dict["emit"].to_string()).or_insert(0) += 1;
+    });
+
+    let mut ret = Dict::new();
+
+    for (emit, count) in counts {
+        ret.insert(emit, RefValue::from(count));
+    }
+
+    RefValue::from(ret).into()
+});
+
+/// Computes the maximum nesting depth of an AST, where leaf nodes have a depth of 1.
+fn tree_depth(node: &RefValue) -> usize {
+    let node = node.borrow();
+
+    if let Some(list) = node.list() {
+        list.iter().map(tree_depth).max().unwrap_or(0)
+    } else if let Some(dict) = node.dict() {
+        if dict.contains_key("emit") {
+            1 + dict.get("children").map(tree_depth).unwrap_or(0)
+        } else {
+            0
+        }
+    } else {
+        0
+    }
+}
+
+tokay_function!("tree_depth(node)", {
+    RefValue::from(tree_depth(&node) as i64).into()
+});
+
+tokay_function!("tree_size(node)", {
+    let mut size = 0;
+    visit_nodes(&node, &mut |_| size += 1);
+    RefValue::from(size as i64).into()
+});
+
+tokay_function!("leaves(node)", {
+    let mut ret = List::new();
+
+    visit_nodes(&node, &mut |dict| {
+        if !dict.contains_key("children") {
+            if let Some(value) = dict.get("value") {
+                ret.push(value.clone());
+            }
+        }
+    });
+
+    RefValue::from(ret).into()
+});
+
+/// Copies an AST without the nodes for which a predicate is truthy.
+fn prune(
+    context: &mut Context,
+    node: &RefValue,
+    func: &RefValue,
+) -> Result<Option<RefValue>, Reject> {
+    let value = node.borrow();
+
+    if let Some(list) = value.list() {
+        let mut ret = List::new();
+
+        for item in list.iter() {
+            if let Some(item) = prune(context, item, func)? {
+                ret.push(item);
+            }
+        }
+
+        return Ok(Some(RefValue::from(ret)));
+    }
+
+    match value.dict() {
+        Some(dict) if dict.contains_key("emit") => {
+            let mut dict = dict.clone();
+            drop(value);
+
+            if context.call(func, vec![node.clone()])?.is_true() {
+                return Ok(None);
+            }
+
+            if let Some(children) = dict.remove("children") {
+                if let Some(children) = prune(context, &children, func)? {
+                    if !matches!(children.borrow().list(), Some(list) if list.is_empty()) {
+                        dict.insert("children".to_string(), children);
+                    }
+                }
+            }
+
+            Ok(Some(RefValue::from(dict)))
+        }
+        _ => Ok(Some(node.clone())),
+    }
+}
+
+tokay_function!("ast_prune(node, func)", {
+    let context = context.unwrap();
+
+    match prune(context, &node, &func)? {
+        Some(node) => node.into(),
+        None => value!(void).into(),
+    }
+});
+
+/// Transforms an AST in post-order, replacing each node by the result of a function.
+fn map(context: &mut Context, node: &RefValue, func: &RefValue) -> Result<RefValue, Reject> {
+    let value = node.borrow();
+
+    if let Some(list) = value.list() {
+        let mut ret = List::new();
+
+        for item in list.iter() {
+            ret.push(map(context, item, func)?);
+        }
+
+        return Ok(RefValue::from(ret));
+    }
+
+    match value.dict() {
+        Some(dict) if dict.contains_key("emit") => {
+            let mut dict = dict.clone();
+            drop(value);
+
+            if let Some(children) = dict.get("children").cloned() {
+                dict.insert("children".to_string(), map(context, &children, func)?);
+            }
+
+            context.call(func, vec![RefValue::from(dict)])
+        }
+        _ => Ok(node.clone()),
+    }
+}
+
+tokay_function!("ast_map(node, func)", {
+    let context = context.unwrap();
+    map(context, &node, &func)?.into()
+});
+
+/** Matches an AST against a pattern tree, collecting bindings of wildcards.
+
+A pattern node with the emit `_` is a wildcard matching any node. When it provides a `bind`
+key, the matched node is bound to this name, and further wildcards with the same name must
+match an equal node. Any other pattern node must provide equal values for all its keys. */
+fn ast_match(node: &RefValue, pattern: &RefValue, bindings: &mut Dict) -> bool {
+    let pattern = pattern.borrow();
+
+    if let Some(pattern) = pattern.dict() {
+        if pattern
+            .get("emit")
+            .is_some_and(|emit| emit.to_string() == "_")
+        {
+            if let Some(name) = pattern.get("bind") {
+                let name = name.to_string();
+
+                if let Some(bound) = bindings.get(&name) {
+                    return bound == node;
+                }
+
+                bindings.insert(name, node.clone());
+            }
+
+            return true;
+        }
+
+        let node = node.borrow();
+
+        if let Some(node) = node.dict() {
+            return pattern.iter().all(|(key, pattern)| match node.get(key) {
+                Some(value) if key == "children" => ast_match(value, pattern, bindings),
+                Some(value) => value == pattern,
+                None => false,
+            });
+        }
+
+        return false;
+    }
+
+    if let Some(pattern) = pattern.list() {
+        let node = node.borrow();
+
+        if let Some(node) = node.list() {
+            return node.len() == pattern.len()
+                && node
+                    .iter()
+                    .zip(pattern.iter())
+                    .all(|(node, pattern)| ast_match(node, pattern, bindings));
+        }
+
+        return false;
+    }
+
+    *node.borrow() == *pattern
+}
+
+tokay_function!("ast_match(node, pattern)", {
+    let mut bindings = Dict::new();
+
+    if ast_match(&node, &pattern, &mut bindings) {
+        RefValue::from(bindings).into()
+    } else {
+        value!(void).into()
+    }
+});
+
+/// Instantiates a template tree, replacing its wildcards by their bound nodes.
+fn instantiate(template: &RefValue, bindings: &Dict) -> RefValue {
+    let value = template.borrow();
+
+    if let Some(dict) = value.dict() {
+        if dict.get("emit").is_some_and(|emit| emit.to_string() == "_") {
+            if let Some(bound) = dict
+                .get("bind")
+                .and_then(|name| bindings.get(&name.to_string()))
+            {
+                return bound.clone();
+            }
+        }
+
+        let mut dict = dict.clone();
+
+        if let Some(children) = dict.get("children").cloned() {
+            dict.insert("children".to_string(), instantiate(&children, bindings));
+        }
+
+        return RefValue::from(dict);
+    }
+
+    if let Some(list) = value.list() {
+        let mut ret = List::new();
+
+        for item in list.iter() {
+            ret.push(instantiate(item, bindings));
+        }
+
+        return RefValue::from(ret);
+    }
+
+    template.clone()
+}
+
+/// Rewrites an AST bottom-up once, returning the new AST and whether anything was rewritten.
+fn rewrite(node: &RefValue, pattern: &RefValue, template: &RefValue) -> (RefValue, bool) {
+    let value = node.borrow();
+
+    if let Some(list) = value.list() {
+        let mut ret = List::new();
+        let mut changed = false;
+
+        for item in list.iter() {
+            let (item, item_changed) = rewrite(item, pattern, template);
+            changed |= item_changed;
+            ret.push(item);
+        }
+
+        return (RefValue::from(ret), changed);
+    }
+
+    match value.dict() {
+        Some(dict) if dict.contains_key("emit") => {
+            let mut dict = dict.clone();
+            drop(value);
+
+            let mut changed = false;
+
+            if let Some(children) = dict.get("children").cloned() {
+                let (children, children_changed) = rewrite(&children, pattern, template);
+                dict.insert("children".to_string(), children);
+                changed = children_changed;
+            }
+
+            let node = RefValue::from(dict);
+            let mut bindings = Dict::new();
+
+            if ast_match(&node, pattern, &mut bindings) {
+                (instantiate(template, &bindings), true)
+            } else {
+                (node, changed)
+            }
+        }
+        _ => (node.clone(), false),
+    }
+}
+
+tokay_function!("ast_rewrite(node, pattern, template, max=void)", {
+    let max = if max.is_void() { 100 } else { max.to_usize() };
+    let mut node = node;
+
+    // Rewrite until a fixpoint is reached, or the maximum number of passes is exceeded.
+    for _ in 0..max {
+        let (rewritten, changed) = rewrite(&node, &pattern, &template);
+        node = rewritten;
+
+        if !changed {
+            break;
+        }
+    }
+
+    node.into()
+});
+
+/** Folds an AST in post-order using visitor callables keyed by emit.
+
+Each visitor is called with the node followed by the results of its walked children.
+Values which are not AST nodes are returned as is, lists are walked item by item. */
+pub fn walk(context: &mut Context, node: &RefValue, visitors: &Dict) -> Result<RefValue, Reject> {
+    let (emit, children) = {
+        let value = node.borrow();
+
+        if let Some(list) = value.list() {
+            let mut ret = List::new();
+
+            for item in list.iter() {
+                ret.push(walk(context, item, visitors)?);
+            }
+
+            return Ok(RefValue::from(ret));
+        }
+
+        match value.dict() {
+            Some(dict) if dict.contains_key("emit") => {
+                (dict["emit"].to_string(), dict.get("children").cloned())
+            }
+            _ => return Ok(node.clone()),
+        }
+    };
+
+    let mut args = vec![node.clone()];
+
+    if let Some(children) = children {
+        if let Some(list) = children.borrow().list() {
+            for child in list.iter() {
+                args.push(walk(context, child, visitors)?);
+            }
+        } else {
+            args.push(walk(context, &children, visitors)?);
+        }
+    }
+
+    if let Some(visitor) = visitors.get(&emit) {
+        context.call(visitor, args)
+    } else {
+        Err(format!("walk has no visitor for emit '{}'", emit).into())
+    }
+}
+
+tokay_function!("walk(node, visitors)", {
+    let context = context.unwrap();
+
+    let visitors = if let Some(visitors) = visitors.borrow().dict() {
+        visitors.clone()
+    } else {
+        return Err(format!(
+            "{} only accepts 'dict' as visitors, not '{}'",
+            __function,
+            visitors.borrow().name()
+        )
+        .into());
+    };
+
+    walk(context, &node, &visitors)?.into()
+});
+
+/// Converts an AST into nested lists of the form `[emit, value, children...]`.
+fn to_sexpr(node: &RefValue) -> RefValue {
+    let value = node.borrow();
+
+    if let Some(list) = value.list() {
+        let mut ret = List::new();
+
+        for item in list.iter() {
+            ret.push(to_sexpr(item));
+        }
+
+        return RefValue::from(ret);
+    }
+
+    match value.dict() {
+        Some(dict) if dict.contains_key("emit") => {
+            let mut ret = List::new();
+            ret.push(dict["emit"].clone());
+
+            if let Some(value) = dict.get("value") {
+                ret.push(value.clone());
+            }
+
+            if let Some(children) = dict.get("children") {
+                if let Some(children) = children.borrow().list() {
+                    for child in children.iter() {
+                        ret.push(to_sexpr(child));
+                    }
+                } else {
+                    ret.push(to_sexpr(children));
+                }
+            }
+
+            RefValue::from(ret)
+        }
+        _ => node.clone(),
+    }
+}
+
+/// Writes DOT nodes and edges for an AST, returning the ids of its top-level nodes.
+fn to_dot(node: &RefValue, dot: &mut String, count: &mut usize) -> Vec<usize> {
+    let node = node.borrow();
+    let mut ids = Vec::new();
+
+    if let Some(list) = node.list() {
+        for item in list.iter() {
+            ids.extend(to_dot(item, dot, count));
+        }
+    } else if let Some(dict) = node.dict() {
+        if let Some(emit) = dict.get("emit") {
+            let id = *count;
+            *count += 1;
+
+            let mut label = emit.to_string();
+            if let Some(value) = dict.get("value") {
+                label.push_str(&format!(" {}", value.repr()));
+            }
+
+            dot.push_str(&format!(
+                "  n{} [label={}];\n",
+                id,
+                RefValue::from(label).repr()
+            ));
+
+            if let Some(children) = dict.get("children") {
+                for child in to_dot(children, dot, count) {
+                    dot.push_str(&format!("  n{} -> n{};\n", id, child));
+                }
+            }
+
+            ids.push(id);
+        }
+    }
+
+    ids
+}
+
+tokay_function!("to_sexpr(node)", to_sexpr(&node).into());
+
+tokay_function!("to_dot(node)", {
+    let mut dot = "digraph {\n".to_string();
+    to_dot(&node, &mut dot, &mut 0);
+    dot.push('}');
+
+    RefValue::from(dot).into()
+});
@@ -45,6 +45,7 @@ pub struct Compiler {
     parser: Option<parser::Parser>,   // Internal Tokay parser
     pub debug: u8,                    // Compiler debug mode
     pub interactive: bool,            // Enable interactive mode (e.g. for REPL)
+    pub verbose_sequences: bool,      // Sequences collect all captures regardless of severity
     pub(super) values: Vec<ImlValue>, // Constant values and parselets created during compile
     pub(super) scopes: Vec<Scope>,    // Current compilation scopes
     pub(super) usages: Vec<Result<Vec<ImlOp>, Usage>>, // Usages of symbols in parselets
@@ -62,6 +63,7 @@ impl Compiler {
                 0
             },
             interactive: false,
+            verbose_sequences: false,
             values: Vec::new(),
             scopes: Vec::new(),
             usages: Vec::new(),
@@ -215,6 +217,28 @@ impl Compiler {
             loops += 1;
         }
 
+        // Compute first-sets of consuming parselets, until no more changes occur.
+        changes = true;
+
+        while changes {
+            changes = false;
+
+            for value in values.iter() {
+                if let ImlValue::Parselet(parselet) = value {
+                    if parselet.borrow().consuming.is_none() {
+                        continue;
+                    }
+
+                    let first = parselet.borrow().first(&values);
+
+                    if first != parselet.borrow().first {
+                        parselet.borrow_mut().first = first;
+                        changes = true;
+                    }
+                }
+            }
+        }
+
         /*
         for i in 0..values.len() {
             if let ImlValue::Parselet(parselet) = &values[i] {
@@ -564,6 +588,26 @@ impl Compiler {
         None
     }
 
+    /** Defines the parselets named in the current scope as values.
+
+    This keeps named parselets in the statics even when nothing references them,
+    so they can be resolved by name at runtime, e.g. by `lookup()`. */
+    pub(super) fn define_parselet_constants(&mut self) {
+        if let Scope::Parselet { constants, .. } = &self.scopes[0] {
+            let mut parselets: Vec<(String, ImlValue)> = constants
+                .iter()
+                .filter(|(_, value)| matches!(value, ImlValue::Parselet(_)))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+
+            parselets.sort_by(|a, b| a.0.cmp(&b.0));
+
+            for (_, parselet) in parselets {
+                self.define_value(parselet);
+            }
+        }
+    }
+
     /** Defines a new constant value for compilation.
     Constants are only being inserted once when they already exist. */
     pub(super) fn define_value(&mut self, value: ImlValue) -> usize {
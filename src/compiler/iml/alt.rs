@@ -49,6 +49,12 @@ impl Compileable for ImlAlternation {
         }
     }
 
+    fn first(&self, values: &[ImlValue]) -> Option<First> {
+        self.items
+            .iter()
+            .fold(None, |ret, alt| First::alternate(ret, alt.first(values)))
+    }
+
     fn compile(&self, parselet: &ImlParselet) -> Vec<Op> {
         let mut ret = Vec::new();
         let mut iter = self.items.iter();
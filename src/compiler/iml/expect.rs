@@ -31,6 +31,10 @@ impl Compileable for ImlExpect {
         self.body.finalize(values, stack)
     }
 
+    fn first(&self, values: &[ImlValue]) -> Option<First> {
+        self.body.first(values)
+    }
+
     fn compile(&self, parselet: &ImlParselet) -> Vec<Op> {
         let body = self.body.compile(parselet);
 
@@ -69,6 +69,10 @@ impl Compileable for ImlIf {
         }
     }
 
+    fn first(&self, values: &[ImlValue]) -> Option<First> {
+        First::alternate(self.then.first(values), self.else_.first(values))
+    }
+
     fn compile(&self, parselet: &ImlParselet) -> Vec<Op> {
         let mut ret = Vec::new();
 
@@ -58,6 +58,17 @@ impl Compileable for ImlLoop {
         ret
     }
 
+    fn first(&self, values: &[ImlValue]) -> Option<First> {
+        // A loop's body may run zero times, so it is always nullable
+        [&self.init, &self.condition, &self.body]
+            .iter()
+            .fold(None, |ret, part| First::alternate(ret, part.first(values)))
+            .map(|first| First {
+                ccl: first.ccl,
+                nullable: true,
+            })
+    }
+
     fn compile(&self, parselet: &ImlParselet) -> Vec<Op> {
         let mut ret = Vec::new();
 
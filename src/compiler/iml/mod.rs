@@ -1,5 +1,6 @@
 //! Tokay intermediate code representation
 pub use crate::vm::*;
+use charclass::CharClass;
 
 mod alt;
 mod expect;
@@ -33,6 +34,56 @@ pub struct Consumable {
     pub nullable: bool, // Flag if consumable is nullable
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct First {
+    pub ccl: CharClass, // Characters which may start consumed input
+    pub nullable: bool, // Flag if consumable is nullable
+}
+
+impl First {
+    /// Merges the first-sets of two alternatives.
+    pub fn alternate(a: Option<First>, b: Option<First>) -> Option<First> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(First {
+                ccl: a.ccl + b.ccl,
+                nullable: a.nullable || b.nullable,
+            }),
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+
+    /// Computes the first-set of a sequence from the first-sets of its items.
+    pub fn sequence(parts: impl Iterator<Item = Option<First>>) -> Option<First> {
+        let mut ret: Option<First> = None;
+
+        for part in parts {
+            if matches!(
+                ret,
+                Some(First {
+                    nullable: false,
+                    ..
+                })
+            ) {
+                break;
+            }
+
+            if let Some(part) = part {
+                ret = Some(if let Some(ret) = ret {
+                    First {
+                        ccl: ret.ccl + part.ccl,
+                        nullable: part.nullable,
+                    }
+                } else {
+                    part
+                });
+            }
+        }
+
+        ret
+    }
+}
+
 pub trait Compileable: std::fmt::Debug + std::fmt::Display {
     /** Resolve any unresolved Usages. */
     fn resolve(&mut self, usages: &mut Vec<Vec<ImlOp>>);
@@ -46,6 +97,10 @@ pub trait Compileable: std::fmt::Debug + std::fmt::Display {
         stack: &mut Vec<(usize, bool)>,
     ) -> Option<Consumable>;
 
+    /** Compute the characters which may start input consumed by this construct.
+    Returns None when the construct doesn't consume anything. */
+    fn first(&self, values: &[ImlValue]) -> Option<First>;
+
     /** Turn intermediate structure into Tokay VM code. */
     fn compile(&self, parselet: &ImlParselet) -> Vec<Op>;
 
@@ -30,6 +30,10 @@ impl Compileable for ImlNot {
         self.body.finalize(values, stack)
     }
 
+    fn first(&self, _values: &[ImlValue]) -> Option<First> {
+        None // A negation never consumes input
+    }
+
     fn compile(&self, parselet: &ImlParselet) -> Vec<Op> {
         let mut ret = Vec::new();
 
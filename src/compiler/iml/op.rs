@@ -1,4 +1,6 @@
 use super::*;
+use crate::value::{Token, Value};
+use charclass::CharClass;
 
 /** Intermediate code representation. */
 
@@ -150,6 +152,43 @@ impl Compileable for ImlOp {
             _ => None,
         }
     }
+
+    fn first(&self, values: &[ImlValue]) -> Option<First> {
+        match self {
+            ImlOp::Compileable(compileable) => compileable.first(values),
+            ImlOp::Ops(ops) => First::sequence(ops.iter().map(|item| item.first(values))),
+            ImlOp::Op(Op::CallStatic(target)) => match &values[*target] {
+                ImlValue::Parselet(parselet) => {
+                    let parselet = parselet.borrow();
+
+                    parselet.consuming.as_ref().map(|consuming| First {
+                        ccl: parselet.first.clone(),
+                        nullable: consuming.nullable,
+                    })
+                }
+                ImlValue::Value(value) => {
+                    if value.is_consuming() {
+                        // Only tokens provide a first-set, anything else may start with any char
+                        let ccl = match &*value.borrow() {
+                            Value::Object(object) => object
+                                .as_ref()
+                                .downcast_ref::<Token>()
+                                .map(|token| token.first()),
+                            _ => None,
+                        };
+
+                        Some(First {
+                            ccl: ccl.unwrap_or_else(|| CharClass::new().negate()),
+                            nullable: value.is_nullable(),
+                        })
+                    } else {
+                        None
+                    }
+                }
+            },
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ImlOp {
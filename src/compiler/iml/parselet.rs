@@ -2,10 +2,12 @@
 
 use super::*;
 use crate::value::Parselet;
+use charclass::CharClass;
 
 #[derive(Debug)]
 pub struct ImlParselet {
     pub consuming: Option<Consumable>,           // Consumable state
+    pub first: CharClass,                        // Characters starting consumed input
     pub severity: u8,                            // Capture push severity
     pub name: Option<String>,                    // Parselet's name from source (for debugging)
     pub signature: Vec<(String, Option<usize>)>, // Argument signature with default arguments
@@ -33,6 +35,7 @@ impl ImlParselet {
         Self {
             name,
             consuming: None,
+            first: CharClass::new(),
             severity: 5,
             signature,
             locals,
@@ -51,6 +54,12 @@ impl ImlParselet {
             } else {
                 None
             },
+            if let Some(Consumable { nullable, .. }) = self.consuming {
+                nullable
+            } else {
+                false
+            },
+            self.consuming.as_ref().map(|_| self.first.clone()),
             self.severity,
             self.signature.clone(),
             self.locals,
@@ -73,6 +82,13 @@ impl ImlParselet {
     ) -> Option<Consumable> {
         self.body.finalize(values, stack)
     }
+
+    pub fn first(&self, values: &[ImlValue]) -> CharClass {
+        self.body
+            .first(values)
+            .map(|first| first.ccl)
+            .unwrap_or_else(CharClass::new)
+    }
 }
 
 impl std::cmp::PartialEq for ImlParselet {
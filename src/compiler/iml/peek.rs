@@ -33,6 +33,10 @@ impl Compileable for ImlPeek {
         self.body.finalize(values, stack)
     }
 
+    fn first(&self, values: &[ImlValue]) -> Option<First> {
+        self.body.first(values)
+    }
+
     fn compile(&self, parselet: &ImlParselet) -> Vec<Op> {
         let mut ret = Vec::new();
 
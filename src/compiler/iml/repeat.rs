@@ -59,6 +59,13 @@ impl Compileable for ImlRepeat {
         }
     }
 
+    fn first(&self, values: &[ImlValue]) -> Option<First> {
+        self.body.first(values).map(|first| First {
+            ccl: first.ccl,
+            nullable: first.nullable || self.min == 0,
+        })
+    }
+
     fn compile(&self, parselet: &ImlParselet) -> Vec<Op> {
         let body = self.body.compile(parselet);
         let body_len = body.len();
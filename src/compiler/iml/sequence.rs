@@ -14,6 +14,7 @@ processed, including data changes, which is a wanted behavior.
 pub struct ImlSequence {
     consuming: Option<Consumable>, // Consumable state
     items: Vec<ImlOp>,
+    verbose: bool, // Collect all captures regardless of their severity
 }
 
 impl ImlSequence {
@@ -21,6 +22,20 @@ impl ImlSequence {
         Self {
             consuming: None,
             items,
+            verbose: false,
+        }
+        .into_op()
+    }
+
+    /** Creates a verbose sequence, which collects all non-empty captures.
+
+    Usually, captures of lower severity are dropped when a sequence is collected,
+    which is not always wanted when debugging a grammar. */
+    pub fn new_verbose(items: Vec<ImlOp>) -> ImlOp {
+        Self {
+            consuming: None,
+            items,
+            verbose: true,
         }
         .into_op()
     }
@@ -95,6 +110,10 @@ impl Compileable for ImlSequence {
         }
     }
 
+    fn first(&self, values: &[ImlValue]) -> Option<First> {
+        First::sequence(self.items.iter().map(|item| item.first(values)))
+    }
+
     fn compile(&self, parselet: &ImlParselet) -> Vec<Op> {
         let mut ret = Vec::new();
 
@@ -104,7 +123,11 @@ impl Compileable for ImlSequence {
 
         if ret.len() > 1 {
             ret.insert(0, Op::Frame(0));
-            ret.push(Op::Collect(0));
+            ret.push(if self.verbose {
+                Op::CollectAll
+            } else {
+                Op::Collect(0)
+            });
             ret.push(Op::Close);
         }
 
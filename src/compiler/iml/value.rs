@@ -3,7 +3,8 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use super::*;
-use crate::value::RefValue;
+use crate::builtin::BuiltinRef;
+use crate::value::{RefValue, Value};
 
 /** Compile-time constant value */
 #[derive(Clone, Debug, PartialEq)]
@@ -37,6 +38,20 @@ impl ImlValue {
         }
     }
 
+    /// Check whether intermediate value is the builtin of the given name
+    pub fn is_builtin(&self, name: &str) -> bool {
+        if let ImlValue::Value(value) = self {
+            if let Value::Object(object) = &*value.borrow() {
+                return object
+                    .as_ref()
+                    .downcast_ref::<BuiltinRef>()
+                    .is_some_and(|builtin| builtin.0.name == name);
+            }
+        }
+
+        false
+    }
+
     /// Check whether intermediate value represents consuming
     pub fn is_consuming(&self) -> bool {
         match self {
@@ -63,6 +63,11 @@ impl Usage {
             } => {
                 // Resolve constants
                 if let Some(value) = compiler.get_constant(&name) {
+                    // Calls to lookup() with a name resolve it directly by Op::LoadByName
+                    if *args == 1 && *nargs == 0 && value.is_builtin("lookup") {
+                        return Some(vec![Op::LoadByName.into()]);
+                    }
+
                     if value.is_callable(*args > 0 || *nargs > 0) {
                         let addr = compiler.define_value(value);
 
@@ -48,7 +48,9 @@ tokay_function!("error(msg, collect=false)", {
     let mut msg = msg.to_string();
 
     if collect.is_true() {
-        if let Ok(Some(value)) = context.collect(context.capture_start, false, true, false, 0) {
+        if let Ok(Some(value)) =
+            context.collect(context.capture_start, false, true, false, 0, false)
+        {
             let value = value.borrow();
 
             if let Value::Str(s) = &*value {
@@ -61,3 +63,17 @@ tokay_function!("error(msg, collect=false)", {
 
     Error::new(Some(context.runtime.reader.tell()), msg).into()
 });
+
+tokay_function!("expect_type(value, type_name)", {
+    let type_name = type_name.to_string();
+
+    if value.name() == type_name {
+        return value.into();
+    }
+
+    Error::new(
+        Some(context.unwrap().runtime.reader.tell()),
+        format!("expected {}, got {}", type_name, value.name()),
+    )
+    .into()
+});
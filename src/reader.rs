@@ -2,6 +2,8 @@
 
 use std::io::prelude::*;
 
+use crate::error::Error;
+
 /// Position inside a reader, with row and column counting.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Offset {
@@ -15,10 +17,12 @@ pub type Range = std::ops::Range<usize>;
 
 // Abstraction of a buffered Reader with internal buffering, offset counting and clean-up.
 pub struct Reader {
-    reader: Box<dyn BufRead>, // Reader object to read from
-    buffer: String,           // Internal buffer
-    offset: Offset,           // Current offset
-    eof: bool,                // EOF marker
+    reader: Box<dyn BufRead>,      // Reader object to read from
+    buffer: String,                // Internal buffer
+    offset: Offset,                // Current offset
+    committed: usize,              // Number of bytes committed before the buffer
+    eof: bool,                     // EOF marker
+    encoding_error: Option<usize>, // Byte offset of invalid UTF-8 input
 }
 
 impl Reader {
@@ -32,28 +36,78 @@ impl Reader {
                 row: 1,
                 col: 1,
             },
+            committed: 0,
             eof: false,
+            encoding_error: None,
         };
 
         ret.peek(); // Peek one character to find out if we're immediately EOF
         ret
     }
 
-    /// Internal function for reading a line.
+    /** Creates a new reader on a byte slice.
+
+    The bytes are decoded as UTF-8 in advance, so invalid input is reported with the
+    byte position where it occurs, instead of silently ending the input there. */
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        match std::str::from_utf8(bytes) {
+            Ok(s) => Ok(Self::new(Box::new(std::io::Cursor::new(s.to_string())))),
+            Err(err) => Err(Error::new(
+                None,
+                format!("Invalid UTF-8 at byte {}", err.valid_up_to()),
+            )),
+        }
+    }
+
+    /** Internal function for reading a line.
+
+    Reading stops at invalid UTF-8 input, whose byte offset is recorded as encoding error. */
     fn read_line(&mut self) -> Option<usize> {
-        if let Ok(n) = self.reader.read_line(&mut self.buffer) {
+        let mut line = Vec::new();
+
+        if let Ok(n) = self.reader.read_until(b'\n', &mut line) {
             if n == 0 {
                 self.eof = true;
                 return None;
             }
 
-            Some(n)
+            match std::str::from_utf8(&line) {
+                Ok(line) => {
+                    self.buffer.push_str(line);
+                    Some(n)
+                }
+                Err(err) => {
+                    let valid = err.valid_up_to();
+
+                    self.encoding_error = Some(self.committed + self.buffer.len() + valid);
+                    self.buffer
+                        .push_str(std::str::from_utf8(&line[..valid]).unwrap());
+                    self.eof = true;
+
+                    if valid > 0 {
+                        Some(valid)
+                    } else {
+                        None
+                    }
+                }
+            }
         } else {
             self.eof = true;
             None
         }
     }
 
+    /// Returns true when reading stopped at invalid UTF-8 input.
+    pub fn had_encoding_error(&self) -> bool {
+        self.encoding_error.is_some()
+    }
+
+    /// Returns an error reporting the byte offset of invalid UTF-8 input, if any.
+    pub fn encoding_error(&self) -> Option<Error> {
+        self.encoding_error
+            .map(|offset| Error::new(None, format!("Invalid UTF-8 at byte {}", offset)))
+    }
+
     pub fn next(&mut self) -> Option<char> {
         loop {
             if let Some(ch) = self.buffer[self.offset.offset..].chars().next() {
@@ -95,6 +149,11 @@ impl Reader {
         self.offset
     }
 
+    /// Absolute position of a buffer position within the entire input.
+    pub fn absolute(&self, pos: usize) -> usize {
+        self.committed + pos
+    }
+
     pub fn eof(&self) -> bool {
         if self.buffer[self.offset.offset..].chars().next().is_some() {
             false
@@ -138,6 +197,7 @@ impl Reader {
     /// Commits current input buffer and removes cached content
     pub fn commit(&mut self) {
         self.buffer.drain(0..self.offset.offset);
+        self.committed += self.offset.offset;
         self.offset.offset = 0;
     }
 }
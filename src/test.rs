@@ -162,6 +162,25 @@ fn expression() {
         ])))
     );
 
+    // Modulo expressions
+    assert_eq!(
+        compile_and_run(
+            "\
+            -7 % 3 \
+            7 % 3 \
+            7.5 % 2 \
+            2 + 10 % 4 * 3 \
+            ",
+            ""
+        ),
+        Ok(Some(value!([(-1), 1, 1.5, 8])))
+    );
+
+    assert_eq!(
+        compile_and_run("x = 0\n7 % x", ""),
+        Err("Line 2, column 1: Cannot divide by zero".to_string())
+    );
+
     // Simple String expressions
     assert_eq!(
         compile_and_run(
@@ -241,6 +260,21 @@ fn expression() {
         ])))
     );
 
+    // Mixed Integer and Float Comparisons, also at runtime
+    assert_eq!(
+        compile_and_run(
+            "
+            i = 1
+            f = 1.0
+            (1 == 1.0) (1 < 1.5) (i == f) (i != f) (i <= f) (2.5 > i) (f < 2) (\"1\" > i)
+            ",
+            ""
+        ),
+        Ok(Some(value!([
+            true, true, true, false, true, true, true, true
+        ])))
+    );
+
     // Logical AND and OR
     assert_eq!(
         compile_and_run(
@@ -570,6 +604,35 @@ fn builtin_tokens() {
     );
 }
 
+#[test]
+// Test for built-in indentation tokens
+fn builtin_tokens_indentation() {
+    let grammar = "
+        Block : @{ Indent Line (Samedent Line)* Dedent }
+        Line : @{
+            Word '\\n' Block
+            Word '\\n'?
+        }
+        Line
+    ";
+
+    assert_eq!(
+        compile_and_run(grammar, "a\n  b\n    c\n  d\ne\n"),
+        Ok(Some(value!([["a", [["b", "c"], "d"]], "e"])))
+    );
+
+    // Multiple levels closed at once, even at EOF without newline
+    assert_eq!(
+        compile_and_run(grammar, "a\n\tb\n\t    c"),
+        Ok(Some(value!(["a", ["b", "c"]])))
+    );
+
+    assert_eq!(
+        compile_and_run(grammar, "a\n    b\n  c\n"),
+        Err("Line 3, column 1: Dedent does not match any outer indentation level".to_string())
+    );
+}
+
 // Tests for parselets ----------------------------------------------------------------------------
 
 #[test]
@@ -621,6 +684,396 @@ fn parselet_leftrec() {
     // todo: More examples here please!
 }
 
+#[test]
+// Testing parselets with and without memoization
+fn parselet_memoize() {
+    use crate::compiler::Compiler;
+    use crate::reader::Reader;
+    use crate::vm::Runtime;
+
+    let program = Compiler::new()
+        .compile_str(
+            "
+            A: @{ ''a'' }
+            X: @{ A ''b'' ; A ''c'' }
+            P: @{ P? X }
+            P
+            ",
+        )
+        .unwrap();
+
+    let mut results = Vec::new();
+
+    for memoize in [true, false] {
+        let mut reader = Reader::new(Box::new(std::io::Cursor::new("abacab")));
+        let mut runtime = Runtime::new(&program, &mut reader);
+        runtime.set_memoize(memoize);
+
+        results.push(program.run(&mut runtime).map_err(|err| err.to_string()));
+    }
+
+    // A limited memo table must not break left-recursion
+    for limit in [0, 1, 2] {
+        let mut reader = Reader::new(Box::new(std::io::Cursor::new("abacab")));
+        let mut runtime = Runtime::with_memo_limit(&program, &mut reader, limit);
+
+        results.push(program.run(&mut runtime).map_err(|err| err.to_string()));
+        assert!(runtime.memo.len() <= limit.max(1));
+    }
+
+    assert_eq!(
+        results[0],
+        Ok(Some(value!([[["a", "b"], ["a", "c"]], ["a", "b"]])))
+    );
+
+    for result in &results[1..] {
+        assert_eq!(&results[0], result);
+    }
+}
+
+#[test]
+// Testing first-sets of parselets
+fn parselet_first_set() {
+    let first_set = |name: &str| {
+        let program = format!(
+            "
+            R : @{{ [a-z] Integer }}
+            S : @{{ 'x' ; R ; '_'? '0' }}
+            X : @{{ R S }}
+            first_set(\"{}\")
+            ",
+            name
+        );
+
+        let value = compile_and_run(&program, "").unwrap().unwrap();
+        let value = value.borrow();
+
+        if let Value::Object(object) = &*value {
+            if let Some(Token::Char(ccl)) = object.as_ref().downcast_ref::<Token>() {
+                return ccl.clone();
+            }
+        }
+
+        panic!("first_set({}) returned {:?}", name, value);
+    };
+
+    let r = first_set("R");
+    assert!(r.test(&('a'..='z')));
+    assert!(!r.test(&('0'..='9')));
+
+    let s = first_set("S");
+    assert!(s.test(&('a'..='z')));
+    assert!(s.test(&('_'..='_')));
+    assert!(s.test(&('0'..='0')));
+    assert!(!s.test(&('1'..='1')));
+}
+
+#[test]
+// Testing ambiguity detection between parselets by their first-sets
+fn parselet_overlaps() {
+    let program = "
+        Ident : @{ [a-z] [a-z0-9]* }
+        Keyword : @{ 'if' ; 'else' }
+        Number : @{ [0-9]+ }
+        X : @{ Ident Keyword Number }
+    ";
+
+    assert_eq!(
+        compile_and_run(&format!("{}overlaps(\"Ident\", \"Keyword\")", program), ""),
+        Ok(Some(value!(true)))
+    );
+
+    assert_eq!(
+        compile_and_run(&format!("{}overlaps(\"Ident\", \"Number\")", program), ""),
+        Ok(Some(value!(false)))
+    );
+}
+
+#[test]
+// Testing lookup of parselets and builtins by name at runtime
+fn parselet_lookup() {
+    assert_eq!(
+        compile_and_run(
+            "
+            Hello : @{ 'hello' \"world\" }
+            Bye : @{ 'bye' \"moon\" }
+            f = lookup(\"Hel\" + \"lo\")
+            f()
+            ",
+            "hello"
+        ),
+        Ok(Some(value!("world")))
+    );
+
+    assert_eq!(
+        compile_and_run("lookup(\"Nope\") == void lookup(\"chr\")(65)", ""),
+        Ok(Some(value!([true, "A"])))
+    );
+
+    // Calls by reference use the builtin instead of Op::LoadByName
+    assert_eq!(
+        compile_and_run("l = lookup\nl(\"chr\")(66)", ""),
+        Ok(Some(value!("B")))
+    );
+}
+
+#[test]
+// Testing parselets constructed at runtime by combinators
+fn parselet_combinators() {
+    assert_eq!(
+        compile_and_run(
+            "
+            p = seq(\"(\", alt(\"x\", @{ Integer }), \")\")
+            p()
+            ",
+            "(42)(x)(y)(7)"
+        ),
+        Ok(Some(value!([
+            ["(", 42, ")"],
+            ["(", "x", ")"],
+            ["(", 7, ")"]
+        ])))
+    );
+
+    // longest prefers the longest match over the first one, ties go to the earliest
+    assert_eq!(
+        compile_and_run(
+            "
+            p = longest(\"in\", \"int\", \"integer\", @{ ''ab'' 1 }, @{ ''ab'' 2 })
+            p _
+            ",
+            "int integer in ab"
+        ),
+        Ok(Some(value!(["int", "integer", "in", 1])))
+    );
+
+    let err = compile_and_run("p = longest(\"a\")\np(x=1)", "").unwrap_err();
+    assert!(err.starts_with("Line 2, column 1: <combinator "));
+    assert!(err.ends_with("> doesn't accept arguments"));
+
+    assert_eq!(
+        compile_and_run("seq(\"a\", 1)", ""),
+        Err("Line 1, column 1: seq() only accepts strings or callables, not 1".to_string())
+    );
+
+    let err = compile_and_run("p = seq(\"a\", \"b\")\np(1)", "").unwrap_err();
+    assert!(err.starts_with("Line 2, column 1: <combinator "));
+    assert!(err.ends_with("> doesn't accept arguments"));
+}
+
+#[test]
+// Testing longest-match of keywords with tries
+fn parselet_trie() {
+    assert_eq!(
+        compile_and_run(
+            "
+            words = (\"in\", \"int\", \"if\", \"integer\")
+            keyword = trie(words)
+            keyword()
+            ",
+            "int in integer inte if i"
+        ),
+        Ok(Some(value!(["int", "in", "integer", "int", "if"])))
+    );
+
+    assert_eq!(
+        compile_and_run("trie((\"a\", 1))", ""),
+        Err("Line 1, column 1: trie() only accepts strings, not 1".to_string())
+    );
+
+    let err = compile_and_run("t = trie((\"in\", \"int\"))\nt(1)", "").unwrap_err();
+    assert!(err.starts_with("Line 2, column 1: <trie "));
+    assert!(err.ends_with("> doesn't accept arguments"));
+}
+
+#[test]
+// Testing negation of character-classes at runtime
+fn token_cc_negate() {
+    assert_eq!(
+        compile_and_run(
+            "
+            not_digit = cc_negate([0-9])
+            not_digit
+            ",
+            "a1b2"
+        ),
+        Ok(Some(value!(["a", "b"])))
+    );
+
+    assert_eq!(
+        compile_and_run("not_digit = cc_negate(\"0-9\")\nnot_digit", "7x"),
+        Ok(Some(value!("x")))
+    );
+
+    assert_eq!(
+        compile_and_run("cc_negate(1)", ""),
+        Err("Line 1, column 1: cc_negate() only accepts character-classes, not 1".to_string())
+    );
+}
+
+#[test]
+// Testing character-classes constructed at runtime
+fn token_ccl() {
+    assert_eq!(
+        compile_and_run("ident = ccl(\"a-zA-Z0-9_\")\nident", "aZ_9-ä"),
+        Ok(Some(value!(["a", "Z", "_", "9"])))
+    );
+
+    assert_eq!(
+        compile_and_run("sign = ccl(\"-+\")\nsign", "+a-"),
+        Ok(Some(value!(["+", "-"])))
+    );
+
+    assert_eq!(
+        compile_and_run("sign = ccl(\"+-\")\nsign", "-x+"),
+        Ok(Some(value!(["-", "+"])))
+    );
+
+    assert_eq!(
+        compile_and_run("not_digit = ccl(\"^0-9-\")\nnot_digit", "1a-2b"),
+        Ok(Some(value!(["a", "b"])))
+    );
+}
+
+#[test]
+// Testing mutable cells shared between parselet calls
+fn parselet_cell() {
+    assert_eq!(
+        compile_and_run(
+            "
+            Count : @c { Word _ cell_set(c, cell_get(c) + 1) }
+            Words : @{
+                n = cell(0)
+                Count(n)+ cell_get(n)
+            }
+            Words
+            ",
+            "one two three"
+        ),
+        Ok(Some(value!(3)))
+    );
+
+    assert_eq!(
+        compile_and_run("cell_get(1)", ""),
+        Err("Line 1, column 1: cell_get() only accepts cells, not 1".to_string())
+    );
+}
+
+#[test]
+// Testing lookahead builtins
+fn parselet_lookahead() {
+    assert_eq!(
+        compile_and_run(
+            "
+            P : @{ x = lookahead(@{ [A-Z]+ }) Word (x, $2) }
+            P
+            ",
+            "ABc de FGH"
+        ),
+        Ok(Some(value![[["AB", "ABc"], ["FGH", "FGH"]]]))
+    );
+
+    assert_eq!(
+        compile_and_run(
+            "
+            P : @{ lookahead_not(@{ ''if'' Whitespace }) Word }
+            P _
+            ",
+            "foo if bar"
+        ),
+        Ok(Some(value![["foo", "f", "bar"]]))
+    );
+}
+
+#[test]
+// Testing capture severity control
+fn parselet_severity() {
+    assert_eq!(
+        compile_and_run("P : @{ ''a'' ''b'' }\nP", "ab"),
+        Ok(Some(value![["a", "b"]]))
+    );
+
+    assert_eq!(
+        compile_and_run("P : @{ ''a'' ''b'' severity(0) }\nP", "ab"),
+        Ok(Some(value!("a")))
+    );
+
+    // Levels which aren't constant are set by the builtin instead of Op::SetSeverity
+    assert_eq!(
+        compile_and_run("P : @level { ''a'' ''b'' severity(level) }\nP(0)", "ab"),
+        Ok(Some(value!("a")))
+    );
+
+    assert_eq!(
+        compile_and_run("P : @{ 'a' severity(5) ''b'' }\nP", "ab"),
+        Ok(Some(value![["a", "b"]]))
+    );
+
+    assert_eq!(
+        compile_and_run("severity(256)", ""),
+        Err("Line 1, column 1: severity() level must be between 0 and 255".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("severity(-1)", ""),
+        Err("Line 1, column 1: severity() level must be between 0 and 255".to_string())
+    );
+}
+
+#[test]
+// Testing manual backtracking with marks
+fn parselet_mark() {
+    assert_eq!(
+        compile_and_run(
+            "
+            P : @{ m = mark() w = Word if optional(''!'') { \"excl \" + w } else { reset_to(m) \"ident \" + Identifier } }
+            P _
+            ",
+            "abc! x1 def!"
+        ),
+        Ok(Some(value!(["excl abc", "ident x1", "excl def"])))
+    );
+
+    assert_eq!(
+        compile_and_run("f = @{ mark() }\nreset_to(f())", ""),
+        Err(
+            "Line 2, column 1: reset_to() cannot restore a mark from another parselet call"
+                .to_string()
+        )
+    );
+
+    assert_eq!(
+        compile_and_run("reset_to(1)", ""),
+        Err("Line 1, column 1: reset_to() only accepts marks, not 1".to_string())
+    );
+}
+
+#[test]
+// Testing grammar introspection of parselets
+fn parselet_introspection() {
+    assert_eq!(
+        compile_and_run(
+            "
+            A : @{ 'a'* }
+            B : @{ 'b'+ }
+            L : @{ L 'x' ; 'x' }
+            X : @{ A B L }
+
+            x = @{ 'x'? }
+            is_nullable(\"A\") is_nullable(\"B\") is_leftrec(\"L\") is_leftrec(\"B\") \
+            is_nullable(x) is_nullable(1)
+            ",
+            ""
+        ),
+        Ok(Some(value!([true, false, true, false, true, false])))
+    );
+
+    assert_eq!(
+        compile_and_run("is_leftrec(\"Y\")", ""),
+        Err("Line 1, column 1: is_leftrec() Parselet 'Y' not found".to_string())
+    );
+}
+
 #[test]
 // Testing compile- and run-time error reporting
 fn parselet_call_error_reporting() {
@@ -841,30 +1294,317 @@ fn loops() {
     run_testcase("tests/err_break_continue.tok");
 }
 
-// Tests for compiler behavior --------------------------------------------------------------------
-
 #[test]
-// Testing several special parsing constructs and error reporting
-fn compiler_error_reporting() {
-    // Test for programs which consist just of one comment
-    assert_eq!(compile_and_run("#tralala", ""), Ok(None));
+// Test for rejecting unconsumed input in parse_all mode
+fn parse_all() {
+    use crate::compiler::Compiler;
+    use crate::reader::Reader;
+    use crate::vm::Runtime;
 
-    // Test for whitespace
+    let program = Compiler::new().compile_str("'a' 'b'").unwrap();
+
+    // Without parse_all, trailing garbage is silently skipped
     assert_eq!(
-        compile_and_run("#normal comment\n#\n\t123", ""),
-        Ok(Some(value!(123)))
+        program.run_from_str("ababx").map_err(|err| err.to_string()),
+        Ok(Some(value![[["a", "b"], ["a", "b"]]]))
     );
 
-    // Test for invalid input when EOF is expected
+    let mut reader = Reader::new(Box::new(std::io::Cursor::new("abab")));
+    let mut runtime = Runtime::new(&program, &mut reader);
+
     assert_eq!(
-        compile_and_run("{}}", ""),
-        Err("Line 1, column 3: Parse error, expecting end-of-file".to_string())
+        program
+            .run_strict(&mut runtime)
+            .map_err(|err| err.to_string()),
+        Ok(Some(value![[["a", "b"], ["a", "b"]]]))
     );
 
-    // Test on unclosed sequences `(1 `
+    let mut reader = Reader::new(Box::new(std::io::Cursor::new("ababx")));
+    let mut runtime = Runtime::new(&program, &mut reader);
+
     assert_eq!(
-        compile_and_run("(1", ""),
-        Err("Line 1, column 3: Expecting \")\"".to_string())
+        program
+            .run_strict(&mut runtime)
+            .map_err(|err| err.to_string()),
+        Err("Line 1, column 5: Expecting end of input".to_string())
+    );
+}
+
+#[test]
+// Test for running programs on byte slices
+fn run_from_bytes() {
+    use crate::compiler::Compiler;
+
+    let program = Compiler::new().compile_str("Word").unwrap();
+
+    let input = "abc d\u{e4}f".to_string();
+
+    assert_eq!(
+        program
+            .run_from_bytes(input.as_bytes())
+            .map_err(|err| err.to_string()),
+        Ok(Some(value![["abc", "d\u{e4}f"]]))
+    );
+
+    assert_eq!(
+        program
+            .run_from_bytes(b"abc \xff")
+            .map_err(|err| err.to_string()),
+        Err("Invalid UTF-8 at byte 4".to_string())
+    );
+}
+
+#[test]
+// Test for invalid UTF-8 in input read by the reader
+fn reader_encoding_error() {
+    use crate::compiler::Compiler;
+    use crate::reader::Reader;
+    use crate::vm::Runtime;
+
+    let program = Compiler::new().compile_str("Word").unwrap();
+
+    let mut reader = Reader::new(Box::new(std::io::Cursor::new(
+        b"abc\ndef g\xffh\nij".to_vec(),
+    )));
+    let mut runtime = Runtime::new(&program, &mut reader);
+
+    assert_eq!(
+        program.run(&mut runtime).map_err(|err| err.to_string()),
+        Err("Invalid UTF-8 at byte 9".to_string())
+    );
+    assert!(reader.had_encoding_error());
+
+    let mut reader = Reader::new(Box::new(std::io::Cursor::new(b"abc\ndef".to_vec())));
+    let mut runtime = Runtime::new(&program, &mut reader);
+
+    assert_eq!(
+        program.run(&mut runtime).map_err(|err| err.to_string()),
+        Ok(Some(value![["abc", "def"]]))
+    );
+    assert!(!reader.had_encoding_error());
+}
+
+#[test]
+#[cfg(feature = "random")]
+// Test for shuffle() and sample() using a seeded runtime
+fn runtime_seed() {
+    use crate::compiler::Compiler;
+    use crate::reader::Reader;
+    use crate::vm::Runtime;
+
+    let program = Compiler::new()
+        .compile_str("l = (1, 2, 3, 4, 5)\nshuffle(l) sample(l, 3) l")
+        .unwrap();
+
+    // The same seed results in the same permutation
+    for _ in 0..2 {
+        let mut reader = Reader::new(Box::new(std::io::Cursor::new("")));
+        let mut runtime = Runtime::new(&program, &mut reader).with_seed(42);
+
+        assert_eq!(
+            program.run(&mut runtime).map_err(|err| err.to_string()),
+            Ok(Some(value![[[1, 2, 5, 4, 3], [3, 5, 1], [1, 2, 3, 4, 5]]]))
+        );
+    }
+
+    assert_eq!(
+        compile_and_run("sample((1, 2), 3)", ""),
+        Err("Line 1, column 1: sample() cannot take 3 items from a list of 2".to_string())
+    );
+}
+
+#[test]
+// Test for limiting the number of operations executed
+fn runtime_max_ops() {
+    use crate::compiler::Compiler;
+    use crate::reader::Reader;
+    use crate::vm::Runtime;
+
+    let input = "a".repeat(1000);
+
+    for (src, input, expected) in [
+        ("Word", "abc def", Ok(Some(value![["abc", "def"]]))),
+        // Runaway loops and left-recursions are stopped
+        (
+            "i = 0\nloop { i++ }",
+            "",
+            Err("instruction limit exceeded".to_string()),
+        ),
+        (
+            "P : @{ P? ''a'' }\nP",
+            input.as_str(),
+            Err("Line 1, column 8: instruction limit exceeded".to_string()),
+        ),
+    ] {
+        let program = Compiler::new().compile_str(src).unwrap();
+        let mut reader = Reader::new(Box::new(std::io::Cursor::new(input.to_string())));
+        let mut runtime = Runtime::new(&program, &mut reader).with_max_ops(1000);
+
+        assert_eq!(
+            program.run(&mut runtime).map_err(|err| err.to_string()),
+            expected
+        );
+    }
+}
+
+#[test]
+// Test for selecting the main parselet by name
+fn program_with_main() {
+    use crate::compiler::Compiler;
+    use crate::vm::Program;
+
+    let program = Compiler::new()
+        .compile_str("Num : @{ Integer }\nName : @{ Word }\nNum ; Name")
+        .unwrap();
+
+    let num = Program::with_main(program.statics.clone(), "Num").unwrap();
+    assert_eq!(
+        num.run_from_str("42").map_err(|err| err.to_string()),
+        Ok(Some(value!(42)))
+    );
+
+    let name = Program::with_main(program.statics.clone(), "Name").unwrap();
+    assert_eq!(
+        name.run_from_str("abc").map_err(|err| err.to_string()),
+        Ok(Some(value!("abc")))
+    );
+
+    assert_eq!(
+        Program::with_main(program.statics.clone(), "Nope")
+            .map(|_| ())
+            .map_err(|err| err.to_string()),
+        Err("Main parselet 'Nope' not found".to_string())
+    );
+}
+
+#[test]
+// Test for streaming results of the main parselet via a callback
+fn run_streaming() {
+    use crate::compiler::Compiler;
+    use crate::reader::Reader;
+
+    let program = Compiler::new().compile_str("Integer ';'").unwrap();
+
+    let input = (0..1000)
+        .map(|i| format!("{};", i))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let mut records = Vec::new();
+
+    assert_eq!(
+        program
+            .run_streaming(
+                Reader::new(Box::new(std::io::Cursor::new(input))),
+                |value| records.push(value)
+            )
+            .map_err(|err| err.to_string()),
+        Ok(None)
+    );
+
+    assert_eq!(records.len(), 1000);
+    assert_eq!(records[0], value!(0));
+    assert_eq!(records[999], value!(999));
+}
+
+#[test]
+// Test for profiling parselet calls
+fn profiling() {
+    use crate::compiler::Compiler;
+    use crate::reader::Reader;
+    use crate::vm::Runtime;
+
+    let program = Compiler::new()
+        .compile_str(
+            "
+            Digit : @{ [0-9] }
+            Number : @{ Digit+ }
+            Numbers : @{ Number (',' Number)* }
+            Numbers
+            end profile()
+            ",
+        )
+        .unwrap();
+
+    // Profiling is disabled by default
+    assert_eq!(
+        program.run_from_str("1").map_err(|err| err.to_string()),
+        Ok(Some(value!(["1", []])))
+    );
+
+    let mut reader = Reader::new(Box::new(std::io::Cursor::new("123,45,6789")));
+    let mut runtime = Runtime::new(&program, &mut reader);
+    runtime.enable_profiling();
+
+    let ret = program.run(&mut runtime).unwrap().unwrap();
+    let profile = runtime.profile();
+
+    let calls = |name: &str| {
+        let entry = profile.get(name).unwrap().borrow();
+        let entry = entry.dict().unwrap();
+        entry.get("calls").unwrap().to_i64()
+    };
+
+    assert!(calls("Digit") > calls("Number"));
+    assert!(calls("Number") > calls("Numbers"));
+    assert_eq!(calls("Numbers"), 1);
+
+    // profile() is available from within the program as well
+    let ret = ret.borrow();
+    let ret = ret.list().unwrap();
+    assert!(ret[1].borrow().dict().unwrap().get("Digit").is_some());
+}
+
+#[test]
+// Test for verbose sequences, collecting captures regardless of their severity
+fn verbose_sequences() {
+    use crate::compiler::Compiler;
+
+    let src = "'a' ''b'' 'c'";
+
+    // By default, silent captures are dropped when a sequence is collected
+    let program = Compiler::new().compile_str(src).unwrap();
+
+    assert_eq!(
+        program.run_from_str("abc").map_err(|err| err.to_string()),
+        Ok(Some(value!("b")))
+    );
+
+    // Verbose sequences keep any non-empty capture
+    let mut compiler = Compiler::new();
+    compiler.verbose_sequences = true;
+    let program = compiler.compile_str(src).unwrap();
+
+    assert_eq!(
+        program.run_from_str("abc").map_err(|err| err.to_string()),
+        Ok(Some(value!(["a", "b", "c"])))
+    );
+}
+
+// Tests for compiler behavior --------------------------------------------------------------------
+
+#[test]
+// Testing several special parsing constructs and error reporting
+fn compiler_error_reporting() {
+    // Test for programs which consist just of one comment
+    assert_eq!(compile_and_run("#tralala", ""), Ok(None));
+
+    // Test for whitespace
+    assert_eq!(
+        compile_and_run("#normal comment\n#\n\t123", ""),
+        Ok(Some(value!(123)))
+    );
+
+    // Test for invalid input when EOF is expected
+    assert_eq!(
+        compile_and_run("{}}", ""),
+        Err("Line 1, column 3: Parse error, expecting end-of-file".to_string())
+    );
+
+    // Test on unclosed sequences `(1 `
+    assert_eq!(
+        compile_and_run("(1", ""),
+        Err("Line 1, column 3: Expecting \")\"".to_string())
     );
 
     assert_eq!(
@@ -977,33 +1717,1305 @@ fn builtins() {
         compile_and_run("repr(\"Hello World\")", ""),
         Ok(Some(value!("\"Hello World\"")))
     );
-}
 
-#[test]
-// Tests for builtin string functions
-fn builtins_str() {
+    // chars/from_chars
+    assert_eq!(
+        compile_and_run("chars(\"abc\")", ""),
+        Ok(Some(value!(["a", "b", "c"])))
+    );
+
+    assert_eq!(
+        compile_and_run("from_chars(chars(\"hello\")) == \"hello\"", ""),
+        Ok(Some(value!(true)))
+    );
+
+    assert_eq!(
+        compile_and_run("from_chars((\"ab\", 1, \"c\"))", ""),
+        Ok(Some(value!("ab1c")))
+    );
+
+    // indent
     assert_eq!(
         compile_and_run(
             "
-            \"abcäöü\".upper() \
-            \"ABCÄÖÜ\".lower() \
-            \"hello world\".replace(\"l\") \
-            \"hello world\".replace(\"l\", n=2) \
-            \"hello world\".replace(\"l\", \"x\") \
-            \"hello world\".replace(\"l\", \"x\", 2) \
-            \"hello world\".replace(\"l\").upper() \
-            #\"Tokay\".upper()[1]  # index is not implemented for now \
+            indent(\"    x\") \
+            indent(\"\\tx\") \
+            indent(\"  \\tx\", 4) \
+            indent(\"\\t \\tx\", tab_width=4) \
+            indent(\"x  \") \
+            ",
+            ""
+        ),
+        Ok(Some(value![[4, 8, 4, 8, 0]]))
+    );
+
+    assert_eq!(
+        compile_and_run("indent(\"\\t\\t x\", -1)", ""),
+        Err("Line 1, column 1: indent() requires a count of at least 0, not -1".to_string())
+    );
+
+    // expand_tabs/unexpand
+    assert_eq!(
+        compile_and_run(
+            "
+            expand_tabs(\"a\\tbc\\td\") \
+            expand_tabs(\"ab\\tc\\n\\tx\", 4) \
+            unexpand(\"          x  y\") \
+            unexpand(\"      a\\n    \\tb\", tab_width=4) \
             ",
             ""
         ),
         Ok(Some(value![[
-            "ABCÄÖÜ",
-            "abcäöü",
-            "heo word",
-            "heo world",
-            "hexxo worxd",
-            "hexxo world",
-            "HEO WORD" //"O"
+            "a       bc      d",
+            "ab  c\n    x",
+            "\t  x  y",
+            "\t  a\n\t\tb"
+        ]]))
+    );
+
+    assert_eq!(
+        compile_and_run("expand_tabs(\"a\\tb\", -1)", ""),
+        Err("Line 1, column 1: expand_tabs() requires a count of at least 0, not -1".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("unexpand(\"    a\", -1)", ""),
+        Err("Line 1, column 1: unexpand() requires a count of at least 0, not -1".to_string())
+    );
+
+    // dedent
+    assert_eq!(
+        compile_and_run(
+            "
+            dedent(\"    if x:\\n        y\\n\\n    z\\n\") \
+            dedent(\"  a\\n   \\n    b\") \
+            dedent(\"  a\\n\\tb\") \
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            "if x:\n    y\n\nz\n",
+            "a\n\n  b",
+            "  a\n\tb"
+        ]]))
+    );
+
+    // wrap
+    assert_eq!(
+        compile_and_run(
+            "wrap(\"The quick brown fox jumps over the  lazy dog\", 10)",
+            ""
+        ),
+        Ok(Some(value![[
+            "The quick",
+            "brown fox",
+            "jumps over",
+            "the lazy",
+            "dog"
+        ]]))
+    );
+
+    assert_eq!(
+        compile_and_run(
+            "
+            wrap(\"a abcdefghij b\", 4) \
+            wrap(\"a abcdefghij b\", 4, false) \
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            ["a", "abcd", "efgh", "ij b"],
+            ["a", "abcdefghij", "b"]
+        ]]))
+    );
+
+    assert_eq!(
+        compile_and_run("wrap(\"a b\", -1)", ""),
+        Err("Line 1, column 1: wrap() requires a count of at least 0, not -1".to_string())
+    );
+
+    // crc32/adler32
+    assert_eq!(
+        compile_and_run(
+            "
+            crc32(\"123456789\") crc32(\"The quick brown fox jumps over the lazy dog\") crc32(\"\") \
+            adler32(\"Wikipedia\") adler32(\"\")
+            ",
+            ""
+        ),
+        Ok(Some(value![[0xcbf43926_i64, 0x414fa339_i64, 0, 0x11e60398_i64, 1]]))
+    );
+
+    // set_union/set_intersection/set_difference
+    assert_eq!(
+        compile_and_run(
+            "
+            a = (1, 2, 2, 3, 4)
+            b = (3, 4, 5, 5, 1)
+            set_union(a, b) set_intersection(a, b) set_difference(a, b) set_difference(b, a)
+            ",
+            ""
+        ),
+        Ok(Some(value![[[1, 2, 3, 4, 5], [1, 3, 4], [2], [5]]]))
+    );
+
+    // pipe
+    assert_eq!(
+        compile_and_run(
+            "pipe(\"  hello \", @s { strip_prefix(s, \"  \") }, @s { strip_suffix(s, \" \") }, str_upper) pipe(42)",
+            ""
+        ),
+        Ok(Some(value![["HELLO", 42]]))
+    );
+
+    assert_eq!(
+        compile_and_run("pipe(1, @x { x + 1 }, @x { x / 0 })", ""),
+        Err("Line 1, column 28: pipe() failed at stage 1: Cannot divide by zero".to_string())
+    );
+
+    // apply
+    assert_eq!(
+        compile_and_run(
+            "
+            add = @a, b { a + b }
+            args = (3, 4)
+            apply(add, args) apply(str_upper, (\"x\",))
+            ",
+            ""
+        ),
+        Ok(Some(value![[7, "X"]]))
+    );
+
+    assert_eq!(
+        compile_and_run(
+            "apply(@pre, post { Word pre + $1 + post }, (\"<\", \">\"))",
+            "abc def"
+        ),
+        Ok(Some(value![["<abc>", "<def>"]]))
+    );
+
+    // clamp
+    assert_eq!(
+        compile_and_run(
+            "clamp(-5, 0, 10) clamp(5, 0, 10) clamp(15, 0, 10) clamp(2.5, 0, 1) clamp(5, 0.5, 10)",
+            ""
+        ),
+        Ok(Some(value![[0, 5, 10, 1.0, 5.0]]))
+    );
+
+    assert_eq!(
+        compile_and_run("clamp(5, 10, 0)", ""),
+        Err("Line 1, column 1: clamp() expects lo <= hi, but 10 > 0".to_string())
+    );
+
+    // round/floor/ceil
+    assert_eq!(
+        compile_and_run(
+            "round(2.5) round(2.4) round(-2.5) floor(2.9) floor(-2.1) ceil(2.1) ceil(-2.9) round(7) floor(7) ceil(7)",
+            ""
+        ),
+        Ok(Some(value![[3, 2, (-3), 2, (-3), 3, (-2), 7, 7, 7]]))
+    );
+
+    // divmod
+    assert_eq!(
+        compile_and_run(
+            "divmod(7, 2) divmod(-7, 2) divmod(7, -2) divmod(-7, -2) divmod(7.5, 2)",
+            ""
+        ),
+        Ok(Some(value![[
+            [3, 1],
+            [(-4), 1],
+            [(-4), (-1)],
+            [3, (-1)],
+            [3.0, 1.5]
         ]]))
     );
+
+    assert_eq!(
+        compile_and_run("divmod(1, 0)", ""),
+        Err("Line 1, column 1: divmod() cannot divide by zero".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("divmod(-9223372036854775807 - 1, -1)", ""),
+        Err("Line 1, column 1: divmod() integer overflow".to_string())
+    );
+
+    // safe_div
+    assert_eq!(
+        compile_and_run(
+            "safe_div(7, 2) safe_div(6, 3) safe_div(1, 0, 42) safe_div(1.5, 0.0, -1) safe_div(1, 0) == void",
+            ""
+        ),
+        Ok(Some(value![[3.5, 2, 42, (-1), true]]))
+    );
+
+    // gensym
+    assert_eq!(
+        compile_and_run(
+            "a = gensym()\nb = gensym()\nc = gensym(\"tmp\")\na b c a != b",
+            ""
+        ),
+        Ok(Some(value![["g0", "g1", "tmp2", true]]))
+    );
+
+    // expect_type
+    assert_eq!(
+        compile_and_run("expect_type(42, \"int\") expect_type(\"x\", \"str\")", ""),
+        Ok(Some(value![[42, "x"]]))
+    );
+
+    assert_eq!(
+        compile_and_run("Word expect_type($1, \"int\")", "abc"),
+        Err("Line 1, column 4: expected int, got str".to_string())
+    );
+
+    // digits/from_digits
+    assert_eq!(
+        compile_and_run(
+            "
+            d = digits(1234)
+            h = digits(255, 16)
+            d h digits(0) from_digits(d) from_digits(h, 16) from_digits(digits(48879, 16), 16)
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            [1, 2, 3, 4],
+            [15, 15],
+            [0],
+            1234,
+            255,
+            48879
+        ]]))
+    );
+
+    assert_eq!(
+        compile_and_run("digits(-1)", ""),
+        Err("Line 1, column 1: digits() only accepts non-negative integers, not -1".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("from_digits((1, 10))", ""),
+        Err("Line 1, column 1: from_digits() requires digits from 0 to 9, not 10".to_string())
+    );
+
+    // band/bor/bxor/bnot/shl/shr
+    assert_eq!(
+        compile_and_run(
+            "band(12, 10) bor(12, 10) bxor(12, 10) bnot(0) shl(1, 4) shr(-16, 2) shl(3, 63) shl(1, 64) shr(-1, 99)",
+            ""
+        ),
+        Ok(Some(value![[
+            8,
+            14,
+            6,
+            (-1),
+            16,
+            (-4),
+            (i64::MIN),
+            0,
+            (-1)
+        ]]))
+    );
+
+    assert_eq!(
+        compile_and_run("band(1, 1.5)", ""),
+        Err("Line 1, column 1: band() only accepts integers, not 1.5".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("shl(1, -1)", ""),
+        Err("Line 1, column 1: shl() cannot shift by negative amount -1".to_string())
+    );
+
+    // gcd/lcm
+    assert_eq!(
+        compile_and_run(
+            "gcd(12, 18) lcm(12, 18) gcd(9, 28) lcm(9, 28) gcd(-4, 6) lcm(-4, 6) gcd(0, 0) gcd(0, 5) lcm(0, 5)",
+            ""
+        ),
+        Ok(Some(value![[6, 36, 1, 252, 2, 12, 0, 5, 0]]))
+    );
+
+    assert_eq!(
+        compile_and_run("lcm(9223372036854775807, 2)", ""),
+        Ok(Some(value!(18446744073709551614.0)))
+    );
+
+    assert_eq!(
+        compile_and_run("gcd(4, 2.5)", ""),
+        Err("Line 1, column 1: gcd() only accepts integers, not 2.5".to_string())
+    );
+
+    // to_base
+    assert_eq!(
+        compile_and_run(
+            "to_base(255, 16) to_base(10, 2) to_base(-35, 36) to_base(0, 8)",
+            ""
+        ),
+        Ok(Some(value![["ff", "1010", "-z", "0"]]))
+    );
+
+    assert_eq!(
+        compile_and_run("to_base(1.5, 2)", ""),
+        Err("Line 1, column 1: to_base() only accepts integers, not 1.5".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("to_base(10, 37)", ""),
+        Err("Line 1, column 1: to_base() requires a base from 2 to 36, not 37".to_string())
+    );
+
+    // scan_all
+    assert_eq!(
+        compile_and_run("scan_all(@{ Integer ; . accept })", "a12 bc 3 x456y"),
+        Ok(Some(value![[12, 3, 456]]))
+    );
+
+    // skip
+    assert_eq!(
+        compile_and_run("skip(3) Word", "abcdef"),
+        Ok(Some(value![["abc", "def"]]))
+    );
+
+    assert_eq!(
+        compile_and_run("Word ' ' skip(10) skip(1)", "ab cdef"),
+        Ok(Some(value![["ab", "cdef"]]))
+    );
+
+    assert_eq!(
+        compile_and_run("skip(-1)", "abc"),
+        Err("Line 1, column 1: skip() requires a count of at least 0, not -1".to_string())
+    );
+
+    // field
+    assert_eq!(
+        compile_and_run(
+            "field(4) field(4) field(4) '\\n'?",
+            "abcd1234wxyz\nefgh5678"
+        ),
+        Ok(Some(value![["abcd", "1234", "wxyz"]]))
+    );
+
+    assert_eq!(
+        compile_and_run("field(-2)", "abc"),
+        Err("Line 1, column 1: field() requires a count of at least 0, not -2".to_string())
+    );
+
+    // find_all
+    assert_eq!(
+        compile_and_run("find_all(@{ Integer })", "a12 bc 3 x456y-7 z"),
+        Ok(Some(value![[12, 3, 456, (-7)]]))
+    );
+
+    // optional
+    assert_eq!(
+        compile_and_run("neg = optional('-') n = Integer _ (neg, n)", "-1 2 -3"),
+        Ok(Some(value![[[true, 1], [false, 2], [true, 3]]]))
+    );
+
+    // capture_range
+    assert_eq!(
+        compile_and_run(
+            "Word ' ' Word '\\n'? capture_range(1) capture_range(3) capture_range(0) capture_range(9) == void",
+            "ab cd\nefg hij"
+        ),
+        Ok(Some(value![[
+            [[0, 2], [3, 5], [0, 6], true],
+            [[6, 9], [10, 13], [6, 13], true]
+        ]]))
+    );
+
+    assert_eq!(
+        compile_and_run("Word capture_range(-1)", "ab"),
+        Err("Line 1, column 6: capture_range() requires a count of at least 0, not -1".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("Word capture_range(9223372036854775807) == void", "ab"),
+        Ok(Some(value!(true)))
+    );
+
+    // peek_str
+    assert_eq!(
+        compile_and_run(
+            "a = peek_str(\"ab\") b = peek_str(\"x\") w = Word (a, b, w)",
+            "abc xy a"
+        ),
+        Ok(Some(value![[
+            [true, false, "abc"],
+            [false, true, "xy"],
+            [false, false, "a"]
+        ]]))
+    );
+}
+
+#[test]
+// Tests for builtin string functions
+fn builtins_str() {
+    assert_eq!(
+        compile_and_run(
+            "
+            \"abcäöü\".upper() \
+            \"ABCÄÖÜ\".lower() \
+            \"hello world\".replace(\"l\") \
+            \"hello world\".replace(\"l\", n=2) \
+            \"hello world\".replace(\"l\", \"x\") \
+            \"hello world\".replace(\"l\", \"x\", 2) \
+            \"hello world\".replace(\"l\").upper() \
+            #\"Tokay\".upper()[1]  # index is not implemented for now \
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            "ABCÄÖÜ",
+            "abcäöü",
+            "heo word",
+            "heo world",
+            "hexxo worxd",
+            "hexxo world",
+            "HEO WORD" //"O"
+        ]]))
+    );
+
+    assert_eq!(
+        compile_and_run("\"hello\".replace(\"\", \"x\")", ""),
+        Err("Line 1, column 8: str_replace() cannot replace an empty string".to_string())
+    );
+
+    // split
+    assert_eq!(
+        compile_and_run(
+            "
+            a = \"a,b,,c\".split(\",\")
+            b = \"a,b,c\".split(\",\", 2)
+            c = \"abc\".split(\"\")
+            d = \"abc\".split(\"\", 2)
+            a b c d \"\".split(\",\")
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            ["a", "b", "", "c"],
+            ["a", "b,c"],
+            ["a", "b", "c"],
+            ["a", "bc"],
+            [""]
+        ]]))
+    );
+
+    // starts_with/ends_with
+    assert_eq!(
+        compile_and_run(
+            "
+            \"hello world\".starts_with(\"hello\") \
+            \"hello world\".starts_with(\"world\") \
+            \"hello world\".starts_with(\"\") \
+            \"hello world\".ends_with(\"world\") \
+            \"hello world\".ends_with(\"hello\") \
+            str_ends_with(\"hello world\", \"\") \
+            ",
+            ""
+        ),
+        Ok(Some(value![[true, false, true, true, false, true]]))
+    );
+
+    // to_int
+    assert_eq!(
+        compile_and_run(
+            "
+            \"42\".to_int() \"-42\".to_int() \"ff\".to_int(16) \"0xFF\".to_int(16) \
+            \"-0o17\".to_int(8) \"0b101\".to_int(2) \"z\".to_int(36)
+            ",
+            ""
+        ),
+        Ok(Some(value![[42, (-42), 255, 255, (-15), 5, 35]]))
+    );
+
+    assert_eq!(
+        compile_and_run("\"0x1f\".to_int()", ""),
+        Err("Line 1, column 7: str_to_int() cannot parse \"0x1f\" with radix 10".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("\"0x-5\".to_int(16)", ""),
+        Err("Line 1, column 7: str_to_int() cannot parse \"0x-5\" with radix 16".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("\"-+5\".to_int()", ""),
+        Err("Line 1, column 6: str_to_int() cannot parse \"-+5\" with radix 10".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("\"12\".to_int(1)", ""),
+        Err("Line 1, column 5: str_to_int() radix must be between 2 and 36, not 1".to_string())
+    );
+
+    // trim/trim_start/trim_end
+    assert_eq!(
+        compile_and_run(
+            "
+            s = \"\\t  hello world \\n\"
+            s.trim() s.trim_start() s.trim_end() s
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            "hello world",
+            "hello world \n",
+            "\t  hello world",
+            "\t  hello world \n"
+        ]]))
+    );
+
+    assert_eq!(
+        compile_and_run(
+            "
+            s = \"--==abc=-\"
+            s.trim(\"-=\") s.trim_start(\"-=\") s.trim_end(\"=-\") s.trim(\"\")
+            ",
+            ""
+        ),
+        Ok(Some(value![["abc", "abc=-", "--==abc", "--==abc=-"]]))
+    );
+
+    // repeat_str
+    assert_eq!(
+        compile_and_run(
+            "repeat_str(@{ \"x\" }, 3, \",\") repeat_str(@{ \"ab\" }, 2) repeat_str(@{ \"x\" }, 0, \",\")",
+            ""
+        ),
+        Ok(Some(value![["x,x,x", "abab", ""]]))
+    );
+
+    assert_eq!(
+        compile_and_run("repeat_str(@{ \"x\" }, -1)", ""),
+        Err("Line 1, column 1: repeat_str() requires a count of at least 0, not -1".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("repeat_str(@{ \"x\" }, 1.5)", ""),
+        Err("Line 1, column 1: repeat_str() only accepts integers, not 1.5".to_string())
+    );
+
+    // is_empty
+    assert_eq!(
+        compile_and_run(
+            "is_empty(void) is_empty(null) is_empty(\"\") is_empty(list()) is_empty(dict()) \
+            is_empty(\" \") is_empty((1,)) is_empty((a => 1)) is_empty(0) is_empty(false)",
+            ""
+        ),
+        Ok(Some(value![[
+            true, true, true, true, true, false, false, false, false, false
+        ]]))
+    );
+
+    // is_nan/is_finite (0.0 / 0.0 is rejected, so NaN and infinity are parsed from strings)
+    assert_eq!(
+        compile_and_run(
+            "is_nan(\"NaN\") is_nan(1.5) is_nan(0) is_finite(1.5) is_finite(42) \
+            is_finite(\"inf\") is_finite(\"NaN\")",
+            ""
+        ),
+        Ok(Some(value![[true, false, false, true, true, false, false]]))
+    );
+
+    // str
+    assert_eq!(
+        compile_and_run(
+            "str(void, \"-\", \"NULL\") str(null, \"-\", \"NULL\") str(void) str(null) str(42) str(\"x\", \"-\")",
+            ""
+        ),
+        Ok(Some(value![["-", "NULL", "", "null", "42", "x"]]))
+    );
+
+    // normalize_newlines
+    assert_eq!(
+        compile_and_run(
+            "s = \"a\\r\\nb\\rc\\nd\"\nnormalize_newlines(s) normalize_newlines(s, \"\\r\\n\")",
+            ""
+        ),
+        Ok(Some(value![["a\nb\nc\nd", "a\r\nb\r\nc\r\nd"]]))
+    );
+
+    // quote
+    assert_eq!(
+        compile_and_run(
+            r#"quote("say \"hi\"") quote("it's", "'") quote("a\\b\n") quote("it's")"#,
+            ""
+        ),
+        Ok(Some(value![[
+            r#""say \"hi\"""#,
+            r#"'it\'s'"#,
+            r#""a\\b\n""#,
+            r#""it's""#
+        ]]))
+    );
+
+    // strip_prefix/strip_suffix
+    assert_eq!(
+        compile_and_run(
+            "strip_prefix(\"foobar\", \"foo\") strip_prefix(\"foobar\", \"bar\") strip_suffix(\"foobar\", \"bar\") strip_suffix(\"foobar\", \"foo\")",
+            ""
+        ),
+        Ok(Some(value![["bar", "foobar", "foo", "foobar"]]))
+    );
+
+    // count_leading/count_trailing
+    assert_eq!(
+        compile_and_run(
+            "count_leading(\"### Title\", \"#\") count_leading(\"Title\", \"#\") count_trailing(\"Title ##\", \"#\") count_trailing(\"\", \"#\")",
+            ""
+        ),
+        Ok(Some(value![[3, 0, 2, 0]]))
+    );
+
+    assert_eq!(
+        compile_and_run("count_leading(\"##\", \"ab\")", ""),
+        Err(
+            "Line 1, column 1: count_leading() requires a single character, not \"ab\"".to_string()
+        )
+    );
+
+    // is_ident
+    assert_eq!(
+        compile_and_run(
+            "is_ident(\"foo_bar1\") is_ident(\"_x\") is_ident(\"1abc\") is_ident(\"\") is_ident(\"foo-bar\") is_ident(\"foo-bar\", void, \"a-z-\") is_ident(\"-foo\", \"a-z\", \"a-z-\")",
+            ""
+        ),
+        Ok(Some(value![[true, true, false, false, false, true, false]]))
+    );
+
+    // to_snake/to_camel/to_pascal
+    assert_eq!(
+        compile_and_run(
+            "s = \"parseHTMLString\"\nto_snake(s) to_camel(s) to_pascal(s) to_camel(to_snake(s)) to_pascal(\"parse_html_string\") to_snake(\"ParseHtml2String\")",
+            ""
+        ),
+        Ok(Some(value![[
+            "parse_html_string",
+            "parseHtmlString",
+            "ParseHtmlString",
+            "parseHtmlString",
+            "ParseHtmlString",
+            "parse_html2_string"
+        ]]))
+    );
+}
+
+#[test]
+// Tests for builtin list functions
+fn builtins_list() {
+    // list_pop
+    assert_eq!(
+        compile_and_run(
+            "
+            l = (1, 2, 3, 4)
+            a = l.pop()
+            b = l.pop(0)
+            c = l.pop(5)
+            a b c == void list().pop() == void l
+            ",
+            ""
+        ),
+        Ok(Some(value![[4, 1, true, true, [2, 3]]]))
+    );
+
+    assert_eq!(
+        compile_and_run("list_pop(1)", ""),
+        Err("Line 1, column 1: list_pop() only accepts 'list' as parameter, not 'int'".to_string())
+    );
+
+    assert_eq!(
+        compile_and_run("(1, 2, 3).pop(1.5)", ""),
+        Err("Line 1, column 10: list_pop() only accepts integers as index, not 1.5".to_string())
+    );
+
+    // list_pad
+    assert_eq!(
+        compile_and_run(
+            "
+            l = (1, 2)
+            a = l.pad(4, 0)
+            b = l.pad(1)
+            c = list_pad(l, 3)
+            a b c l
+            ",
+            ""
+        ),
+        Ok(Some(value![[[1, 2, 0, 0], [1, 2], [1, 2, void], [1, 2]]]))
+    );
+
+    assert_eq!(
+        compile_and_run("(1, 2).pad(-1)", ""),
+        Err("Line 1, column 7: list_pad() requires a length of at least 0, not -1".to_string())
+    );
+
+    // dict_keys/dict_values/dict_items
+    assert_eq!(
+        compile_and_run(
+            "
+            d = (b => 2, a => 1)
+            k = d.keys()
+            v = d.values()
+            i = d.items()
+            k v i
+            ",
+            ""
+        ),
+        Ok(Some(value![[["a", "b"], [1, 2], [["a", 1], ["b", 2]]]]))
+    );
+
+    assert_eq!(
+        compile_and_run("dict_keys(1)", ""),
+        Err(
+            "Line 1, column 1: dict_keys() only accepts 'dict' as parameter, not 'int'".to_string()
+        )
+    );
+
+    // partition
+    assert_eq!(
+        compile_and_run("partition((1, 2, 3, 4, 5), @x { x % 2 == 0 })", ""),
+        Ok(Some(value![[[2, 4], [1, 3, 5]]]))
+    );
+
+    assert_eq!(
+        compile_and_run("partition((1, 2), @x { error(\"Invalid \" + x) })", ""),
+        Err("Line 1, column 1: Invalid 1".to_string())
+    );
+
+    // take_while/drop_while
+    assert_eq!(
+        compile_and_run(
+            "
+            l = (3, 1, 4, -1, 5, -9)
+            take_while(l, @x { x > 0 }) drop_while(l, @x { x > 0 }) take_while(l, @x { x > 9 })
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            [3, 1, 4],
+            [(-1), 5, (-9)],
+            (RefValue::from(List::new()))
+        ]]))
+    );
+
+    // find/find_index
+    assert_eq!(
+        compile_and_run(
+            "
+            l = (3, 5, 8, 7, 10)
+            even = @x { x % 2 == 0 }
+            find(l, even) find_index(l, even) find(l, @x { x > 10 }) == void find_index(l, @x { x < 0 }) == void
+            ",
+            ""
+        ),
+        Ok(Some(value![[8, 2, true, true]]))
+    );
+
+    // all/any
+    assert_eq!(
+        compile_and_run(
+            "all((1, true, \"x\")) all((1, 0, 2)) all((2, 4), @x { x % 2 == 0 }) any((0, false)) any((0, 3))",
+            ""
+        ),
+        Ok(Some(value![[true, false, true, false, true]]))
+    );
+
+    // any() stops at the first truthy element, so 10 / 0 is never evaluated
+    assert_eq!(
+        compile_and_run("any((2, 0), @x { 10 / x })", ""),
+        Ok(Some(value!(true)))
+    );
+
+    // get_path/set_path
+    assert_eq!(
+        compile_and_run(
+            "
+            server = (host => \"localhost\", port => 8080)
+            cfg = (server => server, debug => false)
+            new = set_path(cfg, (\"server\", \"port\"), 9090)
+            get_path(cfg, (\"server\", \"port\")) get_path(new, (\"server\", \"port\")) get_path(new, (\"server\", \"host\")) get_path(cfg, (\"server\", \"user\")) == void get_path((1, 2, 3), 2) get_path((1, 2, 3), 3) == void
+            ",
+            ""
+        ),
+        Ok(Some(value![[8080, 9090, "localhost", true, 3, true]]))
+    );
+
+    // has_keys/missing_keys
+    assert_eq!(
+        compile_and_run(
+            "
+            d = (a => 1, b => 2)
+            has_keys(d, (\"a\", \"b\")) has_keys(d, (\"a\", \"c\")) missing_keys(d, (\"a\", \"c\", \"d\")) missing_keys(d, (\"b\", \"a\"))
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            true,
+            false,
+            ["c", "d"],
+            (RefValue::from(List::new()))
+        ]]))
+    );
+
+    // rle/rle_decode
+    assert_eq!(
+        compile_and_run(
+            "
+            l = (1, 1, 2, \"a\", \"a\", \"a\", 1)
+            rle(l) rle_decode(rle(l)) == l
+            ",
+            ""
+        ),
+        Ok(Some(value![[[[1, 2], [2, 1], ["a", 3], [1, 1]], true]]))
+    );
+
+    assert_eq!(
+        compile_and_run("rle_decode((1, 2))", ""),
+        Err("Line 1, column 1: rle_decode() expects pairs of value and count, not 1".to_string())
+    );
+
+    // merge_strings
+    assert_eq!(
+        compile_and_run(
+            "merge_strings((\"a\", \"b\", 1, \"c\")) merge_strings((1, \"x\", \"y\", \"z\", null))",
+            ""
+        ),
+        Ok(Some(value![[["ab", 1, "c"], [1, "xyz", null]]]))
+    );
+
+    // rotate
+    assert_eq!(
+        compile_and_run(
+            "
+            l = (1, 2, 3, 4)
+            a = rotate(l, 1)
+            b = rotate(l, -1)
+            c = rotate(l, 6)
+            a b c rotate(list(), 3)
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            [2, 3, 4, 1],
+            [4, 1, 2, 3],
+            [3, 4, 1, 2],
+            (RefValue::from(List::new()))
+        ]]))
+    );
+
+    // argmin/argmax
+    assert_eq!(
+        compile_and_run(
+            "
+            l = (3, 1, 4, 1, 5, 9, 2, 9)
+            argmin(l) argmax(l) argmax((-2, 7, 7)) argmin(list()) == void
+            ",
+            ""
+        ),
+        Ok(Some(value![[1, 5, 1, true]]))
+    );
+
+    // transpose
+    assert_eq!(
+        compile_and_run(
+            "
+            a = (1, 2, 3)
+            b = (4, 5, 6)
+            m = (a, b)
+            t = transpose(m)
+            a = (1, 2)
+            b = (3,)
+            m = (a, b)
+            t transpose(m, 0)
+            ",
+            ""
+        ),
+        Ok(Some(value![[[[1, 4], [2, 5], [3, 6]], [[1, 3], [2, 0]]]]))
+    );
+
+    assert_eq!(
+        compile_and_run("a = (1, 2)\nb = (3,)\nm = (a, b)\ntranspose(m)", ""),
+        Err(
+            "Line 4, column 1: transpose() requires rows of equal length, or a fill value"
+                .to_string()
+        )
+    );
+
+    // interleave
+    assert_eq!(
+        compile_and_run(
+            "
+            a = (1, 2, 3)
+            b = (\"a\", \"b\", \"c\")
+            c = (true,)
+            interleave(a, b) interleave(a, c) interleave(c, a, pad=true)
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            [1, "a", 2, "b", 3, "c"],
+            [1, true],
+            [true, 1, void, 2, void, 3]
+        ]]))
+    );
+
+    assert_eq!(
+        compile_and_run("interleave(1, fill=true)", ""),
+        Err("Line 1, column 1: interleave() doesn't accept parameter 'fill'".to_string())
+    );
+
+    // product_of
+    assert_eq!(
+        compile_and_run(
+            "
+            a = (1, 2)
+            b = (\"x\", \"y\")
+            product_of(a, b)
+            ",
+            ""
+        ),
+        Ok(Some(value![[[1, "x"], [1, "y"], [2, "x"], [2, "y"]]]))
+    );
+
+    assert_eq!(
+        compile_and_run("a = (1, 2, 3)\nproduct_of(a, a, max=8)", ""),
+        Err("Line 2, column 1: product_of() exceeds the maximum of 8 combinations".to_string())
+    );
+
+    // chunk_by
+    assert_eq!(
+        compile_and_run(
+            "
+            l = (1, 1, 2, 3, 3)
+            chunk_by(l, @x { x })
+            ",
+            ""
+        ),
+        Ok(Some(value![[[1, 1], [2], [3, 3]]]))
+    );
+
+    assert_eq!(
+        compile_and_run(
+            "
+            l = (1, 2, 3, 4, 1)
+            chunk_by(l, @x { x > 2 })
+            ",
+            ""
+        ),
+        Ok(Some(value![[[1, 2], [3, 4], [1]]]))
+    );
+
+    // scope_new/scope_define/scope_lookup
+    assert_eq!(
+        compile_and_run(
+            "
+            global = scope_new()
+            scope_define(global, \"x\", 1)
+            scope_define(global, \"y\", 2)
+            local = scope_new(global)
+            scope_define(local, \"x\", 3)
+            scope_lookup(local, \"x\") scope_lookup(local, \"y\") scope_lookup(global, \"x\") scope_lookup(local, \"z\") == void
+            ",
+            ""
+        ),
+        Ok(Some(value![[3, 2, 1, true]]))
+    );
+
+    assert_eq!(
+        compile_and_run("scope_lookup(1, \"x\")", ""),
+        Err("Line 1, column 1: scope_lookup() expects a scope, not 1".to_string())
+    );
+}
+
+#[test]
+// Test for folding an AST using emit-keyed visitors
+fn builtin_walk() {
+    assert_eq!(
+        compile_and_run(
+            "
+            mul = (3, 4)
+            mul = (emit => \"mul\", children => mul)
+            add = (2, mul)
+            tree = (emit => \"add\", children => add)
+            visitors = (add => @node, a, b { a + b }, mul => @node, a, b { a * b })
+            walk(tree, visitors)
+            ",
+            ""
+        ),
+        Ok(Some(value!(14)))
+    );
+
+    // Missing visitors are reported
+    assert_eq!(
+        compile_and_run(
+            "walk((emit => \"neg\", children => 1), (add => @node, a, b { a + b }))",
+            ""
+        ),
+        Err("Line 1, column 1: walk has no visitor for emit 'neg'".to_string())
+    );
+}
+
+#[test]
+// Test for AST analysis builtins
+fn builtins_ast() {
+    let tree = "
+        a = (emit => \"int\", value => 1)
+        b = (emit => \"int\", value => 2)
+        c = (emit => \"var\", value => \"x\")
+        l = (b, c)
+        mul = (emit => \"mul\", children => l)
+        l = (a, mul)
+        tree = (emit => \"add\", children => l)
+    ";
+
+    // emit_types
+    assert_eq!(
+        compile_and_run(
+            &format!("{}\nemit_types(tree) emit_types(c) emit_types(42)", tree),
+            ""
+        ),
+        Ok(Some(value![[
+            ["add", "int", "mul", "var"],
+            ["var"],
+            (RefValue::from(List::new()))
+        ]]))
+    );
+
+    // node_counts
+    assert_eq!(
+        compile_and_run(&format!("{}\nnode_counts(tree) node_counts(c)", tree), ""),
+        Ok(Some(value![[
+            ["add" => 1, "int" => 2, "mul" => 1, "var" => 1],
+            ["var" => 1]
+        ]]))
+    );
+
+    // tree_depth and tree_size
+    assert_eq!(
+        compile_and_run(
+            &format!(
+                "{}\ntree_depth(tree) tree_size(tree) tree_depth(c) tree_size(c) \\
+                tree_depth(l) tree_size(l) tree_depth(42) tree_size(42)",
+                tree
+            ),
+            ""
+        ),
+        Ok(Some(value![[3, 5, 1, 1, 2, 4, 0, 0]]))
+    );
+
+    // leaves
+    assert_eq!(
+        compile_and_run(
+            &format!("{}\nleaves(tree) leaves(c) leaves(l) leaves(42)", tree),
+            ""
+        ),
+        Ok(Some(value![[
+            [1, 2, "x"],
+            ["x"],
+            [1, 2, "x"],
+            (RefValue::from(List::new()))
+        ]]))
+    );
+
+    // ast_prune
+    assert_eq!(
+        compile_and_run(
+            &format!(
+                "{}\nis_int = @node {{ get_path(node, \"emit\") == \"int\" }}\n\
+                pruned = ast_prune(tree, is_int)\n\
+                emit_types(pruned) emit_types(tree) ast_prune(a, is_int) == void",
+                tree
+            ),
+            ""
+        ),
+        Ok(Some(value![[
+            ["add", "mul", "var"],
+            ["add", "int", "mul", "var"],
+            true
+        ]]))
+    );
+
+    // ast_map
+    assert_eq!(
+        compile_and_run(
+            &format!(
+                "{}\ndouble = @node {{ \\
+                    if get_path(node, \"emit\") == \"int\" \\
+                        set_path(node, \"value\", get_path(node, \"value\") * 2) \\
+                    else node \\
+                }}\n\
+                mapped = ast_map(tree, double)\n\
+                get_path(mapped, (\"children\", 0, \"value\")) \\
+                get_path(mapped, (\"children\", 1, \"children\", 0, \"value\")) \\
+                get_path(mapped, (\"children\", 1, \"children\", 1, \"value\")) \\
+                get_path(tree, (\"children\", 0, \"value\"))",
+                tree
+            ),
+            ""
+        ),
+        Ok(Some(value![[2, 4, "x", 1]]))
+    );
+
+    // ast_match
+    assert_eq!(
+        compile_and_run(
+            &format!(
+                "{}\nwild = (emit => \"_\", bind => \"rhs\")\n\
+                one = (emit => \"int\", value => 1)\n\
+                l = (one, wild)\n\
+                pattern = (emit => \"add\", children => l)\n\
+                two = (emit => \"int\", value => 2)\n\
+                l = (two, wild)\n\
+                mismatch = (emit => \"add\", children => l)\n\
+                get_path(ast_match(tree, pattern), (\"rhs\", \"emit\")) \\
+                ast_match(tree, mismatch) == void \\
+                ast_match(c, wild) == (rhs => c)",
+                tree
+            ),
+            ""
+        ),
+        Ok(Some(value![["mul", true, true]]))
+    );
+
+    // ast_rewrite
+    assert_eq!(
+        compile_and_run(
+            "
+            x = (emit => \"var\", value => \"x\")
+            zero = (emit => \"int\", value => 0)
+            two = (emit => \"int\", value => 2)
+            l = (x, zero)
+            add1 = (emit => \"add\", children => l)
+            l = (add1, zero)
+            add2 = (emit => \"add\", children => l)
+            l = (two, zero)
+            add3 = (emit => \"add\", children => l)
+            l = (add2, add3)
+            tree = (emit => \"mul\", children => l)
+
+            wild = (emit => \"_\", bind => \"x\")
+            l = (wild, zero)
+            pattern = (emit => \"add\", children => l)
+
+            simple = ast_rewrite(tree, pattern, wild)
+            node_counts(simple) \\
+            get_path(simple, (\"children\", 0)) == x \\
+            get_path(simple, (\"children\", 1)) == two \\
+            node_counts(ast_rewrite(tree, pattern, wild, 0))
+            ",
+            ""
+        ),
+        Ok(Some(value![[
+            ["int" => 1, "mul" => 1, "var" => 1],
+            true,
+            true,
+            ["add" => 3, "int" => 4, "mul" => 1, "var" => 1]
+        ]]))
+    );
+
+    // to_sexpr
+    assert_eq!(
+        compile_and_run(
+            &format!("{}\nto_sexpr(tree) to_sexpr(c) to_sexpr(42)", tree),
+            ""
+        ),
+        Ok(Some(value![[
+            ["add", ["int", 1], ["mul", ["int", 2], ["var", "x"]]],
+            ["var", "x"],
+            42
+        ]]))
+    );
+
+    // to_json
+    assert_eq!(
+        compile_and_run(&format!("{}\nto_json(mul)", tree), ""),
+        Ok(Some(value!(
+            r#"{"children":[{"emit":"int","value":2},{"emit":"var","value":"x"}],"emit":"mul"}"#
+        )))
+    );
+
+    assert_eq!(
+        compile_and_run(
+            r#"
+            l = (1.5, 2.0, true, false, null)
+            a = to_json(l)
+            a to_json("a\"b\\c\n\u0001") to_json(-7) to_json(void)
+            "#,
+            ""
+        ),
+        Ok(Some(value![[
+            "[1.5,2.0,true,false,null]",
+            r#""a\"b\\c\n\u0001""#,
+            "-7",
+            "null"
+        ]]))
+    );
+
+    // to_json output must parse back into the original structure
+    let json = compile_and_run(
+        r#"
+        l = (1, -2.5, true, null)
+        m = (y => null, b => false)
+        d = (z => "a\"b\\c\n\t\r\u0001ä", a => l, m => m)
+        to_json(d)
+        "#,
+        "",
+    )
+    .unwrap()
+    .unwrap()
+    .to_string();
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+        serde_json::json!({
+            "z": "a\"b\\c\n\t\r\u{1}ä",
+            "a": [1, -2.5, true, null],
+            "m": {"y": null, "b": false}
+        })
+    );
+
+    // Dict keys are emitted in sorted order
+    assert_eq!(
+        json,
+        r#"{"a":[1,-2.5,true,null],"m":{"b":false,"y":null},"z":"a\"b\\c\n\t\r\u0001ä"}"#
+    );
+
+    // to_dot
+    assert_eq!(
+        compile_and_run(&format!("{}\nto_dot(tree)", tree), ""),
+        Ok(Some(value!(
+            "digraph {\n  \
+            n0 [label=\"add\"];\n  \
+            n1 [label=\"int 1\"];\n  \
+            n2 [label=\"mul\"];\n  \
+            n3 [label=\"int 2\"];\n  \
+            n4 [label=\"var \\\"x\\\"\"];\n  \
+            n2 -> n3;\n  \
+            n2 -> n4;\n  \
+            n0 -> n1;\n  \
+            n0 -> n2;\n\
+            }"
+        )))
+    );
+}
+
+#[test]
+// Test for dump_captures() describing the current captures
+fn builtin_dump_captures() {
+    assert_eq!(
+        compile_and_run("'a' x => ''b'' 42 dump_captures()", "ab"),
+        Ok(Some(value!([
+            42,
+            [
+                ["kind" => "Range", "severity" => 0, "value" => "a"],
+                ["kind" => "Named", "name" => "x", "severity" => 5, "value" => "b"],
+                ["kind" => "Value", "severity" => 10, "value" => 42]
+            ]
+        ])))
+    );
 }
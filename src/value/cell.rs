@@ -0,0 +1,80 @@
+//! Mutable cells for sharing state across parselet calls
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use macros::tokay_function;
+
+use super::{Object, RefValue, Value};
+use crate::value;
+use crate::vm::*;
+
+/** A cell holds a value that can be replaced later on.
+
+All copies of a cell share the same value, so a cell passed to or captured by several
+parselets can be used for stateful counting during a parse, e.g. to number nodes. */
+#[derive(Debug, Clone)]
+pub struct Cell {
+    value: Rc<RefCell<RefValue>>,
+}
+
+impl Cell {
+    pub fn new(value: RefValue) -> Self {
+        Self {
+            value: Rc::new(RefCell::new(value)),
+        }
+    }
+
+    /// Returns the value currently held by the cell.
+    pub fn get(&self) -> RefValue {
+        self.value.borrow().clone()
+    }
+
+    /// Replaces the value held by the cell.
+    pub fn set(&self, value: RefValue) {
+        *self.value.borrow_mut() = value;
+    }
+}
+
+impl Object for Cell {
+    fn name(&self) -> &'static str {
+        "cell"
+    }
+
+    fn repr(&self) -> String {
+        format!("<cell {}>", self.value.borrow().repr())
+    }
+
+    fn is_callable(&self, _with_arguments: bool) -> bool {
+        false
+    }
+
+    fn is_consuming(&self) -> bool {
+        false
+    }
+}
+
+impl From<Cell> for RefValue {
+    fn from(cell: Cell) -> Self {
+        Value::Object(Box::new(cell)).into()
+    }
+}
+
+// Resolves `value` to a cell, or fails with an error mentioning `function`.
+fn as_cell(function: &str, value: &RefValue) -> Result<Cell, Reject> {
+    if let Value::Object(object) = &*value.borrow() {
+        if let Some(cell) = object.as_ref().downcast_ref::<Cell>() {
+            return Ok(cell.clone());
+        }
+    }
+
+    Err(format!("{} only accepts cells, not {}", function, value.repr()).into())
+}
+
+tokay_function!("cell(init)", RefValue::from(Cell::new(init)).into());
+
+tokay_function!("cell_get(c)", as_cell(__function, &c)?.get().into());
+
+tokay_function!("cell_set(c, v)", {
+    as_cell(__function, &c)?.set(v);
+    value!(void).into()
+});
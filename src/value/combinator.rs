@@ -0,0 +1,164 @@
+//! Parser combinators constructed at runtime
+use macros::tokay_function;
+
+use super::{Dict, List, Object, RefValue, Token, Value};
+use crate::reader::Offset;
+use crate::vm::*;
+
+/** Combinators are consuming callables built from other callables at runtime.
+
+Strings are matched as tokens, any other item must be callable without arguments. */
+#[derive(Debug, Clone)]
+pub enum Combinator {
+    Sequence(Vec<RefValue>),    // Matches all items in order
+    Alternation(Vec<RefValue>), // Matches the first matching item
+    Longest(Vec<RefValue>),     // Matches the item consuming the most input
+}
+
+impl Combinator {
+    // Prepares the items of a combinator, turning strings into tokens.
+    fn items(function: &str, args: Vec<RefValue>) -> Result<Vec<RefValue>, String> {
+        let mut items = Vec::new();
+
+        for arg in args {
+            let item = if let Value::Str(s) = &*arg.borrow() {
+                RefValue::from(Token::Match(s.to_string()))
+            } else if arg.is_callable(false) {
+                arg.clone()
+            } else {
+                return Err(format!(
+                    "{} only accepts strings or callables, not {}",
+                    function,
+                    arg.repr()
+                ));
+            };
+
+            items.push(item);
+        }
+
+        Ok(items)
+    }
+}
+
+impl Object for Combinator {
+    fn name(&self) -> &'static str {
+        "combinator"
+    }
+
+    fn is_callable(&self, with_arguments: bool) -> bool {
+        !with_arguments // Combinators don't support arguments
+    }
+
+    fn is_consuming(&self) -> bool {
+        true
+    }
+
+    fn call(
+        &self,
+        context: &mut Context,
+        args: usize,
+        nargs: Option<Dict>,
+    ) -> Result<Accept, Reject> {
+        if args > 0 || nargs.is_some() {
+            return Err(format!("{} doesn't accept arguments", self.repr()).into());
+        }
+
+        let start = context.runtime.reader.tell();
+
+        match self {
+            Combinator::Sequence(items) => {
+                let mut results = List::new();
+
+                for item in items {
+                    match context.call(item, Vec::new()) {
+                        Ok(value) => {
+                            if !value.is_void() {
+                                results.push(value);
+                            }
+                        }
+                        Err(reject) => {
+                            context.runtime.reader.reset(start);
+                            return Err(reject);
+                        }
+                    }
+                }
+
+                let value = if results.len() == 1 {
+                    results.pop().unwrap()
+                } else {
+                    RefValue::from(results)
+                };
+
+                Ok(Accept::Push(Capture::Value(value, None, 5)))
+            }
+
+            Combinator::Alternation(items) => {
+                for item in items {
+                    match context.call(item, Vec::new()) {
+                        Ok(value) => return Ok(Accept::Push(Capture::Value(value, None, 5))),
+                        Err(Reject::Next) => context.runtime.reader.reset(start),
+                        Err(reject) => return Err(reject),
+                    }
+                }
+
+                Err(Reject::Next)
+            }
+
+            Combinator::Longest(items) => {
+                let mut longest: Option<(RefValue, Offset)> = None;
+
+                // Try every item from the start, ties are won by the earlier item.
+                for item in items {
+                    context.runtime.reader.reset(start);
+
+                    match context.call(item, Vec::new()) {
+                        Ok(value) => {
+                            let end = context.runtime.reader.tell();
+
+                            if longest
+                                .as_ref()
+                                .is_none_or(|(_, longest_end)| end.offset > longest_end.offset)
+                            {
+                                longest = Some((value, end));
+                            }
+                        }
+                        Err(Reject::Next) => {}
+                        Err(reject) => return Err(reject),
+                    }
+                }
+
+                match longest {
+                    Some((value, end)) => {
+                        context.runtime.reader.reset(end);
+                        Ok(Accept::Push(Capture::Value(value, None, 5)))
+                    }
+                    None => {
+                        context.runtime.reader.reset(start);
+                        Err(Reject::Next)
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl From<Combinator> for RefValue {
+    fn from(combinator: Combinator) -> Self {
+        Value::Object(Box::new(combinator)).into()
+    }
+}
+
+tokay_function!("seq(*args)", {
+    RefValue::from(Combinator::Sequence(Combinator::items(__function, args)?)).into()
+});
+
+tokay_function!("alt(*args)", {
+    RefValue::from(Combinator::Alternation(Combinator::items(
+        __function, args,
+    )?))
+    .into()
+});
+
+tokay_function!("longest(*args)", {
+    RefValue::from(Combinator::Longest(Combinator::items(__function, args)?)).into()
+});
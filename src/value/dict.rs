@@ -1,5 +1,5 @@
 //! Dictionary object
-use super::{RefValue, Value};
+use super::{List, RefValue, Value};
 use macros::tokay_method;
 use std::collections::BTreeMap;
 
@@ -83,6 +83,46 @@ impl Dict {
         Ok(dict)
     });
 
+    // Returns the entries of a dict mapped to a list, in the dict's order of keys.
+    fn entries(
+        function: &str,
+        dict: &RefValue,
+        entry: fn(&String, &RefValue) -> RefValue,
+    ) -> Result<RefValue, String> {
+        if let Value::Dict(dict) = &*dict.borrow() {
+            let mut list = List::new();
+
+            for (key, value) in dict.iter() {
+                list.push(entry(key, value));
+            }
+
+            Ok(RefValue::from(list))
+        } else {
+            Err(format!(
+                "{} only accepts 'dict' as parameter, not '{}'",
+                function,
+                dict.borrow().name()
+            ))
+        }
+    }
+
+    tokay_method!("dict_keys(dict)", {
+        Self::entries(__function, &dict, |key, _| RefValue::from(key.clone()))
+    });
+
+    tokay_method!("dict_values(dict)", {
+        Self::entries(__function, &dict, |_, value| value.clone())
+    });
+
+    tokay_method!("dict_items(dict)", {
+        Self::entries(__function, &dict, |key, value| {
+            let mut item = List::new();
+            item.push(RefValue::from(key.clone()));
+            item.push(value.clone());
+            RefValue::from(item)
+        })
+    });
+
     /*
     fn get_index(&self, index: &Value) -> Result<RefValue, String> {
         let index = index.to_string();
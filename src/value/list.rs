@@ -42,6 +42,59 @@ impl List {
         Ok(list)
     });
 
+    tokay_method!("list_pop(list, index=void)", {
+        let mut list = list.borrow_mut();
+
+        if let Value::List(list) = &mut *list {
+            // Pop the last item by default, otherwise the item at the given index
+            let index = match &*index.borrow() {
+                Value::Void => list.len().checked_sub(1),
+                Value::Integer(index) => usize::try_from(*index)
+                    .ok()
+                    .filter(|index| *index < list.len()),
+                _ => {
+                    return Err(format!(
+                        "{} only accepts integers as index, not {}",
+                        __function,
+                        index.repr()
+                    ))
+                }
+            };
+
+            Ok(match index {
+                Some(index) => list.remove(index),
+                None => Value::Void.into(),
+            })
+        } else {
+            Err(format!(
+                "{} only accepts 'list' as parameter, not '{}'",
+                __function,
+                list.name()
+            ))
+        }
+    });
+
+    tokay_method!("list_pad(list, length, fill=void)", {
+        let mut list = List::from(list);
+        let length = match &*length.borrow() {
+            Value::Integer(length) if *length >= 0 => *length as usize,
+            _ => {
+                return Err(format!(
+                    "{} requires a length of at least 0, not {}",
+                    __function,
+                    length.repr()
+                ))
+            }
+        };
+
+        // Lists are only padded, but never truncated
+        if list.len() < length {
+            list.resize(length, fill);
+        }
+
+        Ok(RefValue::from(list))
+    });
+
     pub fn repr(&self) -> String {
         let mut ret = "(".to_string();
         for item in self.iter() {
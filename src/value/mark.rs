@@ -0,0 +1,88 @@
+//! Checkpoints for manual backtracking in semantic code
+use macros::tokay_function;
+
+use super::{Object, RefValue, Value};
+use crate::reader::Offset;
+use crate::vm::*;
+
+/** A mark is a checkpoint of the reader and the captures of the parselet that created it.
+
+Marks can only be restored within the same parselet call they were created in, as the
+stack of any other call is laid out differently. */
+#[derive(Debug, Clone)]
+pub struct Mark {
+    frame: (usize, usize, usize, usize), // Parselet id, depth, stack start and reader start
+    committed: usize,                    // Reader input committed when created
+    offset: Offset,                      // Reader offset
+    stack: usize,                        // Stack size
+}
+
+impl Mark {
+    // Identifies the parselet call of a context.
+    fn frame(context: &Context) -> (usize, usize, usize, usize) {
+        (
+            context.parselet as *const _ as usize,
+            context.depth,
+            context.stack_start,
+            context.reader_start.offset,
+        )
+    }
+}
+
+impl Object for Mark {
+    fn name(&self) -> &'static str {
+        "mark"
+    }
+
+    fn is_callable(&self, _with_arguments: bool) -> bool {
+        false
+    }
+
+    fn is_consuming(&self) -> bool {
+        false
+    }
+}
+
+impl From<Mark> for RefValue {
+    fn from(mark: Mark) -> Self {
+        Value::Object(Box::new(mark)).into()
+    }
+}
+
+tokay_function!("mark()", {
+    let context = context.unwrap();
+
+    RefValue::from(Mark {
+        frame: Mark::frame(context),
+        committed: context.runtime.reader.absolute(0),
+        offset: context.runtime.reader.tell(),
+        stack: context.runtime.stack.len(),
+    })
+    .into()
+});
+
+tokay_function!("reset_to(mark)", {
+    let context = context.unwrap();
+
+    let mark = match &*mark.borrow() {
+        Value::Object(object) => object.as_ref().downcast_ref::<Mark>().cloned(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("{} only accepts marks, not {}", __function, mark.repr()))?;
+
+    if mark.frame != Mark::frame(context)
+        || mark.committed != context.runtime.reader.absolute(0)
+        || mark.stack < context.capture_start
+    {
+        return Err(format!(
+            "{} cannot restore a mark from another parselet call",
+            __function
+        )
+        .into());
+    }
+
+    context.runtime.reader.reset(mark.offset);
+    context.runtime.stack.truncate(mark.stack);
+
+    Ok(Accept::Next)
+});
@@ -6,21 +6,29 @@ use crate::builtin::Builtin;
 use crate::error::Error;
 use crate::vm::{Accept, Context, Reject};
 
+pub mod cell;
+pub mod combinator;
 pub mod dict;
 pub mod list;
+pub mod mark;
 mod method;
 mod object;
-mod parselet;
+pub mod parselet;
 pub mod str;
 pub mod token;
+pub mod trie;
 
 pub use self::str::Str;
+pub use cell::Cell;
+pub use combinator::Combinator;
 pub use dict::Dict;
 pub use list::List;
+pub use mark::Mark;
 pub use method::Method;
 pub use object::Object;
 pub use parselet::{Parselet, ParseletRef};
 pub use token::Token;
+pub use trie::Trie;
 
 // RefValue
 // ----------------------------------------------------------------------------
@@ -104,6 +112,11 @@ impl RefValue {
         self.borrow().repr()
     }
 
+    /// Get representation in JSON.
+    pub fn to_json(&self) -> String {
+        self.borrow().to_json()
+    }
+
     /// Get a value's boolean meaning.
     pub fn is_void(&self) -> bool {
         matches!(&*self.borrow(), Value::Void)
@@ -282,6 +295,48 @@ impl RefValue {
         }
     }
 
+    // Remainder (modulo)
+    pub fn rem(&self, rhs: RefValue) -> Result<RefValue, Error> {
+        // todo: This must be moved to trait Object...
+        match (&*self.borrow(), &*rhs.borrow()) {
+            // When one is Float...
+            (Value::Float(_), _) | (_, Value::Float(_)) => {
+                let a = self.to_f64();
+                let b = rhs.to_f64();
+
+                if b == 0.0 {
+                    return Err("Cannot divide by zero".into());
+                }
+
+                Ok(Value::Float(a % b).into())
+            }
+
+            // ...otherwise, all is assumed as integer.
+            (a, b) => {
+                let a = a.to_i64();
+                let b = b.to_i64();
+
+                if b == 0 {
+                    return Err("Cannot divide by zero".into());
+                }
+
+                Ok(Value::Integer(a.wrapping_rem(b)).into())
+            }
+        }
+    }
+
+    /** Comparison
+
+    Integers and floats are compared numerically by promoting the integer to float.
+    Any other values are compared by their type first, then by their content. */
+    pub fn compare(&self, rhs: &RefValue) -> Option<std::cmp::Ordering> {
+        match (&*self.borrow(), &*rhs.borrow()) {
+            (Value::Integer(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+            (Value::Float(a), Value::Integer(b)) => a.partial_cmp(&(*b as f64)),
+            (a, b) => a.partial_cmp(b),
+        }
+    }
+
     // Negation
     pub fn neg(&self) -> Result<RefValue, Error> {
         match &*self.borrow() {
@@ -477,6 +532,58 @@ impl Value {
         }
     }
 
+    /** Get representation in JSON.
+
+    Void is serialized as null, as well as floats which are not finite. Any other objects
+    are serialized as strings holding their Tokay representation. */
+    pub fn to_json(&self) -> String {
+        fn json_str(s: &str) -> String {
+            let mut ret = String::with_capacity(s.len() + 2);
+            ret.push('"');
+
+            for ch in s.chars() {
+                match ch {
+                    '"' => ret.push_str("\\\""),
+                    '\\' => ret.push_str("\\\\"),
+                    '\n' => ret.push_str("\\n"),
+                    '\r' => ret.push_str("\\r"),
+                    '\t' => ret.push_str("\\t"),
+                    ch if (ch as u32) < 0x20 => ret.push_str(&format!("\\u{:04x}", ch as u32)),
+                    ch => ret.push(ch),
+                }
+            }
+
+            ret.push('"');
+            ret
+        }
+
+        match self {
+            Self::Void | Self::Null => "null".to_string(),
+            Self::True => "true".to_string(),
+            Self::False => "false".to_string(),
+            Self::Integer(i) => format!("{}", i),
+            Self::Addr(a) => format!("{}", a),
+            Self::Float(f) if f.is_finite() => format!("{:?}", f),
+            Self::Float(_) => "null".to_string(),
+            Self::Str(s) => json_str(s.as_str()),
+            Self::List(l) => format!(
+                "[{}]",
+                l.iter()
+                    .map(|item| item.to_json())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            Self::Dict(d) => format!(
+                "{{{}}}",
+                d.iter()
+                    .map(|(key, value)| format!("{}:{}", json_str(key), value.to_json()))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            Self::Object(object) => json_str(&object.repr()),
+        }
+    }
+
     /// Get a value's boolean meaning.
     pub fn is_true(&self) -> bool {
         match self {
@@ -3,9 +3,12 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use super::{Dict, List, Object, RefValue, Value};
+use super::{Dict, List, Object, RefValue, Token, Value};
 use crate::error::Error;
+use crate::value;
 use crate::vm::*;
+use charclass::CharClass;
+use macros::tokay_function;
 
 /** Parselet is the conceptual building block of a Tokay program.
 
@@ -24,6 +27,8 @@ the generated parse tree automatically until no more input can be consumed.
 pub struct Parselet {
     pub(crate) name: Option<String>, // Parselet's name from source (for debugging)
     pub(crate) consuming: Option<bool>, // Indicator for consuming & left-recursion
+    pub(crate) nullable: bool,       // Indicator for nullable consumption
+    pub(crate) first: Option<CharClass>, // Characters starting consumed input
     pub(crate) severity: u8,         // Capture push severity
     signature: Vec<(String, Option<usize>)>, // Argument signature with default arguments
     pub(crate) locals: usize,        // Number of local variables present
@@ -37,6 +42,8 @@ impl Parselet {
     pub fn new(
         name: Option<String>,
         consuming: Option<bool>,
+        nullable: bool,
+        first: Option<CharClass>,
         severity: u8,
         signature: Vec<(String, Option<usize>)>,
         locals: usize,
@@ -52,6 +59,8 @@ impl Parselet {
         Self {
             name,
             consuming,
+            nullable,
+            first,
             severity,
             signature,
             locals,
@@ -161,7 +170,11 @@ impl Parselet {
 
                         Accept::Repeat(value) => {
                             if let Some(value) = value {
-                                results.push(value);
+                                // When streaming, main results are passed on instead of collected
+                                match context.runtime.on_result.as_mut() {
+                                    Some(on_result) if main => on_result(value),
+                                    _ => results.push(value),
+                                }
                             }
                         }
 
@@ -189,6 +202,11 @@ impl Parselet {
                                 .len()
                                 == 0
                         {
+                            // In parse_all mode, unconsumed input is left to the caller
+                            if context.runtime.parse_all {
+                                break None;
+                            }
+
                             context.runtime.reader.next();
                         }
 
@@ -217,6 +235,11 @@ impl Parselet {
 
                     // Skip character and reset reader start
                     if main && state.is_none() {
+                        if context.runtime.parse_all {
+                            context.runtime.reader.reset(context.reader_start);
+                            break None;
+                        }
+
                         context.runtime.reader.next();
                         context.reader_start = context.runtime.reader.tell();
                     } else if results.len() > 0 && state.is_none() {
@@ -282,7 +305,8 @@ impl Parselet {
         let id = self as *const Parselet as usize;
 
         // When parselet is consuming, try to read previous result from cache.
-        if self.consuming.is_some() {
+        // Left-recursive parselets always depend on the cache.
+        if self.consuming == Some(true) || (self.consuming.is_some() && runtime.memoize) {
             // Get unique parselet id from memory address
             let reader_start = runtime.reader.tell();
 
@@ -384,11 +408,13 @@ impl Parselet {
             let mut reader_end = context.reader_start;
             let mut result = Err(Reject::Next);
 
-            // Insert a fake memo entry to avoid endless recursion
-            context.runtime.memo.insert(
-                (context.reader_start.offset, id),
-                (reader_end, result.clone()),
-            );
+            // Insert a fake memo entry to avoid endless recursion,
+            // which must not be evicted while the loop is active.
+            let key = (context.reader_start.offset, id);
+            context.runtime.memo_pinned.push(key);
+            context
+                .runtime
+                .memo_insert(key, (reader_end, result.clone()));
 
             loop {
                 let loop_result = self._run(&mut context, main);
@@ -417,10 +443,9 @@ impl Parselet {
                 reader_end = loop_end;
 
                 // Save intermediate result in memo table
-                context.runtime.memo.insert(
-                    (context.reader_start.offset, id),
-                    (reader_end, result.clone()),
-                );
+                context
+                    .runtime
+                    .memo_insert(key, (reader_end, result.clone()));
 
                 // Reset reader & stack
                 context.runtime.reader.reset(context.reader_start);
@@ -431,14 +456,15 @@ impl Parselet {
                     .resize(context.capture_start, Capture::Empty);
             }
 
+            context.runtime.memo_pinned.pop();
             context.runtime.reader.reset(reader_end);
 
             result
         } else {
             let result = self._run(&mut context, main);
 
-            if !main && self.consuming.is_some() {
-                context.runtime.memo.insert(
+            if !main && self.consuming.is_some() && context.runtime.memoize {
+                context.runtime.memo_insert(
                     (context.reader_start.offset, id),
                     (context.runtime.reader.tell(), result.clone()),
                 );
@@ -503,14 +529,117 @@ impl Object for ParseletRef {
         self.0.borrow().consuming.is_some()
     }
 
+    fn is_nullable(&self) -> bool {
+        self.0.borrow().nullable
+    }
+
     fn call(
         &self,
         context: &mut Context,
         args: usize,
         nargs: Option<Dict>,
     ) -> Result<Accept, Reject> {
-        self.0
-            .borrow()
-            .run(context.runtime, args, nargs, false, context.depth + 1)
+        let parselet = self.0.borrow();
+
+        // Only pay for time measurement when profiling is enabled
+        if let (Some(_), Some(name)) = (&context.runtime.profile, &parselet.name) {
+            let start = std::time::Instant::now();
+            let ret = parselet.run(context.runtime, args, nargs, false, context.depth + 1);
+
+            if let Some(profile) = context.runtime.profile.as_mut() {
+                let entry = profile.entry(name.clone()).or_default();
+                entry.0 += 1;
+                entry.1 += start.elapsed();
+            }
+
+            return ret;
+        }
+
+        parselet.run(context.runtime, args, nargs, false, context.depth + 1)
     }
 }
+
+/** Retrieves a parselet from a value, or by its name from the running program.
+
+Constant parselets can't be passed as values, as they are called when used in expressions,
+therefore they can be referred by name. */
+fn get_parselet(
+    context: Option<&mut Context>,
+    parselet: &RefValue,
+) -> Result<Option<ParseletRef>, String> {
+    let value = parselet.borrow();
+
+    match &*value {
+        Value::Str(name) => {
+            if let Some(value) =
+                context.and_then(|context| context.runtime.program.lookup(name.as_str()))
+            {
+                if let Value::Object(object) = &*value.borrow() {
+                    if let Some(parselet) = object.as_ref().downcast_ref::<ParseletRef>() {
+                        return Ok(Some(parselet.clone()));
+                    }
+                }
+            }
+
+            Err(format!("Parselet '{}' not found", name.as_str()))
+        }
+        Value::Object(object) => Ok(object.as_ref().downcast_ref::<ParseletRef>().cloned()),
+        _ => Ok(None),
+    }
+}
+
+tokay_function!("is_leftrec(parselet)", {
+    match get_parselet(context, &parselet) {
+        Ok(Some(parselet)) => {
+            RefValue::from(matches!(parselet.0.borrow().consuming, Some(true))).into()
+        }
+        Ok(None) => value!(false).into(),
+        Err(msg) => Err(format!("{} {}", __function, msg).into()),
+    }
+});
+
+tokay_function!("is_nullable(parselet)", {
+    match get_parselet(context, &parselet) {
+        Ok(Some(parselet)) => RefValue::from(parselet.0.borrow().nullable).into(),
+        Ok(None) => RefValue::from(parselet.is_nullable()).into(),
+        Err(msg) => Err(format!("{} {}", __function, msg).into()),
+    }
+});
+
+tokay_function!("first_set(parselet)", {
+    match get_parselet(context, &parselet) {
+        Ok(Some(parselet)) => match &parselet.0.borrow().first {
+            Some(ccl) => RefValue::from(Token::Char(ccl.clone())).into(),
+            None => value!(void).into(),
+        },
+        Ok(None) => value!(void).into(),
+        Err(msg) => Err(format!("{} {}", __function, msg).into()),
+    }
+});
+
+tokay_function!("overlaps(a, b)", {
+    let mut context = context;
+    let mut first = |value: &RefValue| match get_parselet(context.as_deref_mut(), value) {
+        Ok(Some(parselet)) => Ok(parselet
+            .0
+            .borrow()
+            .first
+            .clone()
+            .unwrap_or_else(CharClass::new)),
+        Ok(None) => match &*value.borrow() {
+            Value::Object(object) => Ok(object
+                .as_ref()
+                .downcast_ref::<Token>()
+                .map(|token| token.first())
+                .unwrap_or_else(CharClass::new)),
+            _ => Ok(CharClass::new()),
+        },
+        Err(msg) => Err(format!("{} {}", __function, msg)),
+    };
+
+    let a = first(&a)?;
+    let b = first(&b)?;
+
+    // Both first-sets intersect when their union is smaller than their sizes summed up.
+    RefValue::from(a.len() + b.len() > (a + b).len()).into()
+});
@@ -30,6 +30,12 @@ impl Str {
         ret
     }
 
+    tokay_method!("str_ends_with(str, suffix)", {
+        Ok(RefValue::from(
+            str.to_string().ends_with(&suffix.to_string()),
+        ))
+    });
+
     tokay_method!("str_join(str, list)", {
         let delimiter = str.to_string();
         let list = List::from(list);
@@ -56,6 +62,10 @@ impl Str {
         let from = from.to_string();
         let to = to.to_string();
 
+        if from.is_empty() {
+            return Err(format!("{} cannot replace an empty string", __function));
+        }
+
         Ok(RefValue::from(if n.is_void() {
             string.replace(&from, &to)
         } else {
@@ -63,6 +73,133 @@ impl Str {
         }))
     });
 
+    tokay_method!("str_split(str, delimiter, limit=void)", {
+        let string = str.to_string();
+        let delimiter = delimiter.to_string();
+
+        // A positive limit caps the number of items, like Rust's splitn()
+        let limit = if limit.is_void() || limit.to_i64() <= 0 {
+            usize::MAX
+        } else {
+            limit.to_usize()
+        };
+
+        let mut list = List::new();
+
+        if delimiter.is_empty() {
+            // An empty delimiter splits into characters, the last item holds the rest
+            for (i, ch) in string.char_indices() {
+                if list.len() + 1 == limit {
+                    list.push(RefValue::from(&string[i..]));
+                    break;
+                }
+
+                list.push(RefValue::from(ch.to_string()));
+            }
+
+            if list.is_empty() {
+                list.push(RefValue::from(""));
+            }
+        } else {
+            for item in string.splitn(limit, &delimiter) {
+                list.push(RefValue::from(item));
+            }
+        }
+
+        Ok(RefValue::from(list))
+    });
+
+    tokay_method!("str_starts_with(str, prefix)", {
+        Ok(RefValue::from(
+            str.to_string().starts_with(&prefix.to_string()),
+        ))
+    });
+
+    tokay_method!("str_to_int(str, radix=void)", {
+        let string = str.to_string();
+        let radix = if radix.is_void() { 10 } else { radix.to_i64() };
+
+        if !(2..=36).contains(&radix) {
+            return Err(format!(
+                "{} radix must be between 2 and 36, not {}",
+                __function, radix
+            ));
+        }
+
+        // Split off the sign and a prefix matching the radix, like "-0xff"
+        let (sign, digits) = match string.strip_prefix(['-', '+']) {
+            Some(digits) => (&string[..1], digits),
+            None => ("", string.as_str()),
+        };
+
+        let prefix = match radix {
+            16 => Some(["0x", "0X"]),
+            8 => Some(["0o", "0O"]),
+            2 => Some(["0b", "0B"]),
+            _ => None,
+        };
+
+        let digits = prefix
+            .and_then(|prefix| prefix.iter().find_map(|prefix| digits.strip_prefix(prefix)))
+            .unwrap_or(digits);
+
+        // A sign is only accepted in front of the prefix, so "0x-5" is invalid
+        let int = if digits.starts_with(['-', '+']) {
+            None
+        } else {
+            i64::from_str_radix(&format!("{}{}", sign, digits), radix as u32).ok()
+        };
+
+        match int {
+            Some(int) => Ok(RefValue::from(int)),
+            None => Err(format!(
+                "{} cannot parse {} with radix {}",
+                __function,
+                str.repr(),
+                radix
+            )),
+        }
+    });
+
+    tokay_method!("str_trim(str, chars=void)", {
+        let string = str.to_string();
+
+        if chars.is_void() {
+            Ok(RefValue::from(string.trim()))
+        } else {
+            let chars: Vec<char> = chars.to_string().chars().collect();
+            Ok(RefValue::from(
+                string.trim_matches(|ch| chars.contains(&ch)),
+            ))
+        }
+    });
+
+    tokay_method!("str_trim_end(str, chars=void)", {
+        let string = str.to_string();
+
+        if chars.is_void() {
+            Ok(RefValue::from(string.trim_end()))
+        } else {
+            let chars: Vec<char> = chars.to_string().chars().collect();
+            Ok(RefValue::from(
+                string.trim_end_matches(|ch| chars.contains(&ch)),
+            ))
+        }
+    });
+
+    tokay_method!("str_trim_start(str, chars=void)", {
+        let string = str.to_string();
+
+        if chars.is_void() {
+            Ok(RefValue::from(string.trim_start()))
+        } else {
+            let chars: Vec<char> = chars.to_string().chars().collect();
+            Ok(RefValue::from(
+                string.trim_start_matches(|ch| chars.contains(&ch)),
+            ))
+        }
+    });
+
     tokay_method!("str_upper(str)", {
         Ok(RefValue::from(str.to_string().to_uppercase()))
     });
@@ -1,7 +1,8 @@
 //! Token callables represented by Value::Token
-use macros::tokay_token;
+use macros::{tokay_function, tokay_token};
 
 use super::{Dict, Object, RefValue, Value};
+use crate::error::Error;
 use crate::reader::Reader;
 use crate::vm::*;
 use charclass::{charclass, CharClass};
@@ -179,6 +180,20 @@ impl Token {
             }
         }
     }
+
+    /// Characters which may start input matched by this token.
+    pub fn first(&self) -> CharClass {
+        match self {
+            Token::Void | Token::EOF => CharClass::new(),
+            Token::Char(ccl) | Token::Chars(ccl) => ccl.clone(),
+            // Builtin character checks can't be enumerated, so any character is assumed.
+            Token::BuiltinChar(_) | Token::BuiltinChars(_) => CharClass::new().negate(),
+            Token::Match(s) | Token::Touch(s) => match s.chars().next() {
+                Some(ch) => charclass![ch],
+                None => CharClass::new(),
+            },
+        }
+    }
 }
 
 impl Object for Token {
@@ -237,10 +252,19 @@ impl From<Token> for RefValue {
 
 // Hard-coded Tokens are builtins, but they are consumable.
 
+// Characters starting and continuing C-style identifiers
+fn identifier_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_'
+}
+
+fn identifier_continue(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
 // Matching C-style identifiers
 tokay_token!("Identifier", {
     if let Some(ch) = context.runtime.reader.peek() {
-        if !ch.is_alphabetic() && ch != '_' {
+        if !identifier_start(ch) {
             return Err(Reject::Next);
         }
 
@@ -252,7 +276,7 @@ tokay_token!("Identifier", {
     let mut count: usize = 1;
 
     while let Some(ch) = context.runtime.reader.peek() {
-        if !ch.is_alphanumeric() && ch != '_' {
+        if !identifier_continue(ch) {
             break;
         }
 
@@ -271,6 +295,97 @@ tokay_token!("Identifier", {
     }
 });
 
+/* Builds a character class from a string in character-class notation, like "a-zA-Z_".
+A '-' at the start or end of the string is taken literally. */
+fn charclass_from_str(s: &str) -> CharClass {
+    let mut ccl = CharClass::new();
+    let chars: Vec<char> = s.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if i + 2 < chars.len() && chars[i + 1] == '-' {
+            ccl.add(chars[i]..=chars[i + 2]);
+            i += 3;
+        } else {
+            ccl.add(chars[i]..=chars[i]);
+            i += 1;
+        }
+    }
+
+    ccl
+}
+
+// Checks whether a string is an identifier, optionally by custom character classes
+tokay_function!("is_ident(s, start_class=void, continue_class=void)", {
+    let start = if start_class.is_void() {
+        None
+    } else {
+        Some(charclass_from_str(&start_class.to_string()))
+    };
+
+    let cont = if continue_class.is_void() {
+        None
+    } else {
+        Some(charclass_from_str(&continue_class.to_string()))
+    };
+
+    let is_start = |ch: char| match &start {
+        Some(ccl) => ccl.test(&(ch..=ch)),
+        None => identifier_start(ch),
+    };
+
+    let is_continue = |ch: char| match &cont {
+        Some(ccl) => ccl.test(&(ch..=ch)),
+        None => identifier_continue(ch),
+    };
+
+    let s = s.to_string();
+    let mut chars = s.chars();
+
+    let valid = match chars.next() {
+        Some(ch) => is_start(ch) && chars.all(is_continue),
+        None => false,
+    };
+
+    RefValue::from(valid).into()
+});
+
+// Negates a character-class token, or a string in character-class notation
+tokay_function!("cc_negate(cc)", {
+    let token = match &*cc.borrow() {
+        Value::Str(s) => Some(Token::Char(charclass_from_str(s.as_str()).negate())),
+        Value::Object(object) => match object.as_ref().downcast_ref::<Token>() {
+            Some(Token::Char(ccl)) => Some(Token::Char(ccl.clone().negate())),
+            Some(Token::Chars(ccl)) => Some(Token::Chars(ccl.clone().negate())),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match token {
+        Some(token) => RefValue::from(token).into(),
+        None => Err(format!(
+            "{} only accepts character-classes, not {}",
+            __function,
+            cc.repr()
+        )
+        .into()),
+    }
+});
+
+/* Builds a consuming character token from a character-class spec at runtime, like "a-zA-Z0-9_".
+A leading '^' negates the class. */
+tokay_function!("ccl(spec)", {
+    let spec = spec.to_string();
+
+    let ccl = match spec.strip_prefix('^') {
+        Some(spec) => charclass_from_str(spec).negate(),
+        None => charclass_from_str(&spec),
+    };
+
+    RefValue::from(Token::Char(ccl)).into()
+});
+
 // Matching 64-bit integers directly
 tokay_token!("Integer", {
     let mut neg = false;
@@ -361,3 +476,105 @@ tokay_token!("Word(min=void max=void)", {
         Err(Reject::Next)
     }
 });
+
+// Indentation tokens for grammars using the off-side rule, like Python or YAML.
+
+/** Reads the indentation width at the start of a line, and returns it together with
+the current indentation level. Tabs are expanded to the next multiple of 8.
+
+Indentation levels opened on this row or behind are discarded, as they are left-overs
+from a previous, backtracked parse at this position. Returns None when not at the start
+of a line. */
+fn read_indentation(context: &mut Context) -> Option<(usize, usize)> {
+    let reader = &mut context.runtime.reader;
+    let start = reader.tell();
+
+    // EOF without a trailing newline is treated like the start of a following line.
+    let row = if start.col == 1 {
+        start.row
+    } else if reader.eof() {
+        start.row + 1
+    } else {
+        return None;
+    };
+
+    let indents = &mut context.runtime.indents;
+    while matches!(indents.last(), Some((_, opened)) if *opened >= row) {
+        indents.pop();
+    }
+
+    let mut width = 0;
+
+    while let Some(ch) = reader.peek() {
+        match ch {
+            ' ' => width += 1,
+            '\t' => width += 8 - width % 8,
+            _ => break,
+        }
+
+        reader.next();
+    }
+
+    Some((width, indents.last().map_or(0, |(level, _)| *level)))
+}
+
+// Matches a deeper indentation at the start of a line, and opens a new indentation level.
+tokay_token!("Indent", {
+    let start = context.runtime.reader.tell();
+
+    if let Some((width, level)) = read_indentation(context) {
+        if width > level {
+            context.runtime.indents.push((width, start.row));
+            return Ok(Accept::Next);
+        }
+    }
+
+    context.runtime.reader.reset(start);
+    Err(Reject::Next)
+});
+
+// Matches the current indentation at the start of a line.
+tokay_token!("Samedent", {
+    let start = context.runtime.reader.tell();
+
+    if let Some((width, level)) = read_indentation(context) {
+        if width == level {
+            return Ok(Accept::Next);
+        }
+    }
+
+    context.runtime.reader.reset(start);
+    Err(Reject::Next)
+});
+
+// Matches a lower indentation at the start of a line or at EOF, and closes the current
+// indentation level without consuming any input. Multiple levels are closed by multiple calls.
+tokay_token!("Dedent", {
+    let start = context.runtime.reader.tell();
+    let indentation = read_indentation(context);
+    context.runtime.reader.reset(start);
+
+    if let Some((width, level)) = indentation {
+        if width < level {
+            context.runtime.indents.pop();
+
+            if width
+                > context
+                    .runtime
+                    .indents
+                    .last()
+                    .map_or(0, |(level, _)| *level)
+            {
+                return Error::new(
+                    Some(start),
+                    "Dedent does not match any outer indentation level".to_string(),
+                )
+                .into();
+            }
+
+            return Ok(Accept::Next);
+        }
+    }
+
+    Err(Reject::Next)
+});
@@ -0,0 +1,136 @@
+//! Prefix trees for fast matching of word lists
+use std::collections::BTreeMap;
+
+use macros::tokay_function;
+
+use super::{Dict, List, Object, RefValue, Value};
+use crate::vm::*;
+
+// A node of the trie, with its outgoing edges and whether a word ends here.
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    edges: BTreeMap<char, usize>,
+    terminal: bool,
+}
+
+/** A trie is a consuming callable matching the longest word of a word list.
+
+Matching walks the input only once, instead of trying each word like an alternation of
+tokens does. */
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<TrieNode>, // The first node is the root
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![TrieNode::default()],
+        }
+    }
+}
+
+impl Trie {
+    /// Inserts a word into the trie.
+    pub fn insert(&mut self, word: &str) {
+        let mut node = 0;
+
+        for ch in word.chars() {
+            node = match self.nodes[node].edges.get(&ch) {
+                Some(next) => *next,
+                None => {
+                    let next = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    self.nodes[node].edges.insert(ch, next);
+                    next
+                }
+            };
+        }
+
+        self.nodes[node].terminal = true;
+    }
+}
+
+impl Object for Trie {
+    fn name(&self) -> &'static str {
+        "trie"
+    }
+
+    fn is_callable(&self, with_arguments: bool) -> bool {
+        !with_arguments // Tries don't support arguments
+    }
+
+    fn is_consuming(&self) -> bool {
+        true
+    }
+
+    fn is_nullable(&self) -> bool {
+        self.nodes[0].terminal
+    }
+
+    fn call(
+        &self,
+        context: &mut Context,
+        args: usize,
+        nargs: Option<Dict>,
+    ) -> Result<Accept, Reject> {
+        if args > 0 || nargs.is_some() {
+            return Err(format!("{} doesn't accept arguments", self.repr()).into());
+        }
+
+        let reader = &mut context.runtime.reader;
+        let start = reader.tell();
+        let mut longest = None;
+        let mut node = 0;
+
+        // Walk down the trie, remembering the end of the longest word seen so far.
+        loop {
+            if self.nodes[node].terminal {
+                longest = Some(reader.tell());
+            }
+
+            match reader
+                .peek()
+                .and_then(|ch| self.nodes[node].edges.get(&ch).cloned())
+            {
+                Some(next) => {
+                    reader.next();
+                    node = next;
+                }
+                None => break,
+            }
+        }
+
+        if let Some(end) = longest {
+            reader.reset(end);
+            Ok(Accept::Push(Capture::Range(
+                reader.capture_from(&start),
+                None,
+                5,
+            )))
+        } else {
+            reader.reset(start);
+            Err(Reject::Next)
+        }
+    }
+}
+
+impl From<Trie> for RefValue {
+    fn from(trie: Trie) -> Self {
+        Value::Object(Box::new(trie)).into()
+    }
+}
+
+tokay_function!("trie(words)", {
+    let mut trie = Trie::default();
+
+    for word in List::from(words).into_iter() {
+        if let Value::Str(word) = &*word.borrow() {
+            trie.insert(word.as_str());
+        } else {
+            return Err(format!("{} only accepts strings, not {}", __function, word.repr()).into());
+        }
+    }
+
+    RefValue::from(trie).into()
+});
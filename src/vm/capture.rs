@@ -2,7 +2,12 @@ use crate::reader::{Range, Reader};
 use crate::value;
 use crate::value::RefValue;
 
-/// Captures are stack items where the VM operates on.
+/** Captures are stack items where the VM operates on.
+
+The severity decides which captures are taken when a sequence is collected.
+Values pushed by operations get a severity of 10, matches and values returned
+by parselets default to 5, and silent matches (touches) as well as degraded
+captures have a severity of 0. It can be changed using Op::SetSeverity. */
 #[derive(Debug, Clone)]
 pub enum Capture {
     Empty,                               // Empty capture
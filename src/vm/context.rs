@@ -4,6 +4,7 @@ use std::iter::FromIterator;
 
 use super::*;
 use crate::reader::Offset;
+use crate::value;
 use crate::value::{Dict, List, Parselet, RefValue};
 
 /** Contexts represent stack frames for parselet calls.
@@ -81,6 +82,17 @@ impl<'runtime, 'program, 'reader, 'parselet> Context<'runtime, 'program, 'reader
         Ok(Accept::Push(Capture::Value(value, None, 10)))
     }
 
+    /// Set severity of the capture on top of the stack, when it belongs to this context.
+    pub fn set_severity(&mut self, severity: u8) {
+        if self.runtime.stack.len() > self.capture_start {
+            self.runtime
+                .stack
+                .last_mut()
+                .unwrap()
+                .set_severity(severity);
+        }
+    }
+
     /// Pop value off the stack.
     #[inline]
     pub fn pop(&mut self) -> RefValue {
@@ -105,6 +117,24 @@ impl<'runtime, 'program, 'reader, 'parselet> Context<'runtime, 'program, 'reader
         self.push(value)
     }
 
+    /** Calls a callable value with the given arguments from Rust code.
+
+    This is used by builtins calling back into Tokay code, e.g. to evaluate a predicate.
+    The result of the call is returned as value, which is void when nothing was returned. */
+    pub fn call(&mut self, callable: &RefValue, args: Vec<RefValue>) -> Result<RefValue, Reject> {
+        let count = args.len();
+
+        for arg in args {
+            self.runtime.stack.push(Capture::Value(arg, None, 0));
+        }
+
+        match callable.call(self, count, None)? {
+            Accept::Push(mut capture) => Ok(capture.extract(self.runtime.reader)),
+            Accept::Return(Some(value)) | Accept::Repeat(Some(value)) => Ok(value),
+            _ => Ok(value!(void)),
+        }
+    }
+
     /** Return a capture by index as RefValue. */
     pub fn get_capture(&mut self, pos: usize) -> Option<RefValue> {
         let pos = self.capture_start + pos - 1;
@@ -206,7 +236,7 @@ impl<'runtime, 'program, 'reader, 'parselet> Context<'runtime, 'program, 'reader
     them either into a dict or list object capture or take them as is.
 
     This function is internally used for automatic AST construction and value
-    inheriting.
+    inheriting. In verbose mode, captures of any severity are collected.
     */
     pub(crate) fn collect(
         &mut self,
@@ -215,6 +245,7 @@ impl<'runtime, 'program, 'reader, 'parselet> Context<'runtime, 'program, 'reader
         single: bool,
         mut inherit: bool,
         severity: u8,
+        verbose: bool,
     ) -> Result<Option<RefValue>, Capture> {
         if capture_start > self.runtime.stack.len() {
             return Ok(None);
@@ -265,8 +296,8 @@ impl<'runtime, 'program, 'reader, 'parselet> Context<'runtime, 'program, 'reader
         // Collect any significant captures and values
         for capture in captures.into_iter() {
             match capture {
-                Capture::Range(range, alias, severity) if severity >= max => {
-                    if severity > max {
+                Capture::Range(range, alias, severity) if verbose || severity >= max => {
+                    if !verbose && severity > max {
                         max = severity;
                         list.clear();
                         dict.clear();
@@ -283,8 +314,8 @@ impl<'runtime, 'program, 'reader, 'parselet> Context<'runtime, 'program, 'reader
                     }
                 }
 
-                Capture::Value(value, alias, severity) if severity >= max => {
-                    if severity > max {
+                Capture::Value(value, alias, severity) if verbose || severity >= max => {
+                    if !verbose && severity > max {
                         max = severity;
                         list.clear();
                         dict.clear();
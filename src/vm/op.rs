@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::io;
 use std::io::prelude::*;
 
@@ -27,6 +28,7 @@ pub enum Op {
     Reset,          // Reset frame
     Close,          // Close frame
     Collect(usize), // Collect stack values from current frame
+    CollectAll,     // Collect stack values from current frame regardless of severity
     Fuse(usize),    // Set frame fuse to forward address
 
     // Loop frames
@@ -72,6 +74,7 @@ pub enum Op {
 
     // Constants
     LoadStatic(usize), // Push a constant from the statics
+    LoadByName,        // Push a parselet or builtin by the name on TOS, or Void
     Push0,             // Push Integer(0)
     Push1,             // Push Integer(1)
     PushVoid,          // Push Void
@@ -98,6 +101,7 @@ pub enum Op {
     StoreIndex,
     StoreIndexHold,
 
+    SetSeverity(u8), // Set severity of the capture on TOS
     MakeAlias,       // Make key-value-Capture from last two stack items
     MakeDict(usize), // Make a Dict from specified amount of key-value-pairs
 
@@ -111,6 +115,7 @@ pub enum Op {
     Sub, // binary sub
     Mul, // binary mul
     Div, // binary div
+    Mod, // binary mod
 
     Not, // unary not (! operator)
     Neg, // unary negation (- operator)
@@ -179,6 +184,18 @@ impl Op {
         while ip < ops.len() {
             let op = &ops[ip];
 
+            // Count operations when limited
+            if let Some(max_ops) = context.runtime.max_ops {
+                context.runtime.ops += 1;
+
+                if context.runtime.ops > max_ops {
+                    return Err(Reject::Error(Box::new(Error::new(
+                        None,
+                        "instruction limit exceeded".to_string(),
+                    ))));
+                }
+            }
+
             // Debug
             if debug == 3 {
                 context.debug(&format!("{:03}:{}", ip, op));
@@ -249,7 +266,22 @@ impl Op {
                 }
 
                 Op::Collect(severity) => {
-                    match context.collect(frame.capture_start, false, true, true, *severity as u8) {
+                    match context.collect(
+                        frame.capture_start,
+                        false,
+                        true,
+                        true,
+                        *severity as u8,
+                        false,
+                    ) {
+                        Err(capture) => Ok(Accept::Push(capture)),
+                        Ok(Some(value)) => Ok(Accept::Push(Capture::Value(value, None, 5))),
+                        Ok(None) => Ok(Accept::Next),
+                    }
+                }
+
+                Op::CollectAll => {
+                    match context.collect(frame.capture_start, false, true, true, 0, true) {
                         Err(capture) => Ok(Accept::Push(capture)),
                         Ok(Some(value)) => Ok(Accept::Push(Capture::Value(value, None, 5))),
                         Ok(None) => Ok(Accept::Next),
@@ -472,6 +504,11 @@ impl Op {
                     let value = &context.runtime.program.statics[*addr];
                     context.push(value.borrow().clone().into())
                 }
+                Op::LoadByName => {
+                    let name = context.pop().to_string();
+                    let value = context.runtime.program.lookup(&name);
+                    context.push(value.unwrap_or_else(|| value!(void)))
+                }
                 Op::Push0 => context.push(value!(0 as i64)),
                 Op::Push1 => context.push(value!(1 as i64)),
                 Op::PushVoid => context.push(value!(void)),
@@ -653,6 +690,11 @@ impl Op {
                     context.push(RefValue::from(dict))
                 }
 
+                Op::SetSeverity(severity) => {
+                    context.set_severity(*severity);
+                    Ok(Accept::Next)
+                }
+
                 // Operations
                 Op::Drop => {
                     context.pop();
@@ -681,7 +723,7 @@ impl Op {
                 }
 
                 // Operations
-                Op::Add | Op::Sub | Op::Mul | Op::Div => {
+                Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Mod => {
                     let b = context.pop();
                     let a = context.pop();
 
@@ -692,10 +734,11 @@ impl Op {
                     */
 
                     let c = match op {
-                        Op::Add => a.add(b)?.into(),
-                        Op::Sub => a.sub(b)?.into(),
-                        Op::Mul => a.mul(b)?.into(),
-                        Op::Div => a.div(b)?.into(),
+                        Op::Add => a.add(b)?,
+                        Op::Sub => a.sub(b)?,
+                        Op::Mul => a.mul(b)?,
+                        Op::Div => a.div(b)?,
+                        Op::Mod => a.rem(b)?,
                         _ => unimplemented!("Unimplemented operator"),
                     };
 
@@ -715,13 +758,19 @@ impl Op {
                     //println!("a = {:?}", a);
                     //println!("b = {:?}", b);
 
+                    let ordering = a.compare(&b);
+
                     let c = match op {
-                        Op::Equal => a == b,
-                        Op::NotEqual => a != b,
-                        Op::LowerEqual => a <= b,
-                        Op::GreaterEqual => a >= b,
-                        Op::Lower => a < b,
-                        Op::Greater => a > b,
+                        Op::Equal => ordering == Some(Ordering::Equal),
+                        Op::NotEqual => ordering != Some(Ordering::Equal),
+                        Op::LowerEqual => {
+                            matches!(ordering, Some(Ordering::Less | Ordering::Equal))
+                        }
+                        Op::GreaterEqual => {
+                            matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+                        }
+                        Op::Lower => ordering == Some(Ordering::Less),
+                        Op::Greater => ordering == Some(Ordering::Greater),
 
                         _ => unimplemented!("Unimplemented operator"),
                     };
@@ -2,6 +2,7 @@ use std::fs::File;
 use std::io::{self, BufReader};
 
 use super::*;
+use crate::builtin::Builtin;
 use crate::error::Error;
 use crate::reader::Reader;
 use crate::value::{ParseletRef, RefValue, Value};
@@ -20,7 +21,7 @@ impl Program {
         let mut main = None;
 
         // Find main parselet by selecting the last parselet defined.
-        // todo: allow to specify main parselet.
+        // Use Program::with_main() to specify the main parselet by name.
         for i in (0..statics.len()).rev() {
             if statics[i].is("parselet") {
                 main = Some(i);
@@ -31,6 +32,48 @@ impl Program {
         Self { statics, main }
     }
 
+    /** Creates a program with the named parselet as its main parselet.
+
+    This allows to choose the entry point of a program made of named parselets at runtime,
+    instead of relying on the order of definition. */
+    pub fn with_main(statics: Vec<RefValue>, name: &str) -> Result<Self, Error> {
+        let main = statics.iter().position(|value| match &*value.borrow() {
+            Value::Object(object) => object
+                .as_ref()
+                .downcast_ref::<ParseletRef>()
+                .is_some_and(|parselet| parselet.0.borrow().name.as_deref() == Some(name)),
+            _ => false,
+        });
+
+        match main {
+            Some(main) => Ok(Self {
+                statics,
+                main: Some(main),
+            }),
+            None => Err(Error::new(
+                None,
+                format!("Main parselet '{}' not found", name),
+            )),
+        }
+    }
+
+    /** Looks up a value by name at runtime.
+
+    Named parselets from the statics are preferred, then builtins are searched. */
+    pub fn lookup(&self, name: &str) -> Option<RefValue> {
+        for value in self.statics.iter() {
+            if let Value::Object(object) = &*value.borrow() {
+                if let Some(parselet) = object.as_ref().downcast_ref::<ParseletRef>() {
+                    if parselet.0.borrow().name.as_deref() == Some(name) {
+                        return Some(value.clone());
+                    }
+                }
+            }
+        }
+
+        Builtin::get(name).map(RefValue::from)
+    }
+
     pub fn dump(&self) {
         for i in 0..self.statics.len() {
             println!("{} => {:#?}", i, self.statics[i]);
@@ -39,7 +82,7 @@ impl Program {
 
     pub fn run(&self, runtime: &mut Runtime) -> Result<Option<RefValue>, Error> {
         if let Some(main) = self.main {
-            match match &*self.statics[main].borrow() {
+            let result = match &*self.statics[main].borrow() {
                 // todo: This is absolutely unhandy.
                 Value::Object(main) => {
                     if let Some(main) = main.as_ref().downcast_ref::<ParseletRef>() {
@@ -51,7 +94,23 @@ impl Program {
                     }
                 }
                 _ => panic!(),
-            } {
+            };
+
+            // Input which stopped at invalid UTF-8 is an error, instead of silently ending there
+            let result = match runtime.reader.encoding_error() {
+                Some(error) if !matches!(result, Err(Reject::Error(_))) => Err(error.into()),
+                _ => result,
+            };
+
+            // In parse_all mode, any remaining input is an error
+            if runtime.parse_all && result.is_ok() && !runtime.reader.eof() {
+                return Err(Error::new(
+                    Some(runtime.reader.tell()),
+                    "Expecting end of input".to_string(),
+                ));
+            }
+
+            match result {
                 Ok(Accept::Push(Capture::Value(value, ..))) => {
                     if value.is_void() {
                         Ok(None)
@@ -68,6 +127,32 @@ impl Program {
         }
     }
 
+    /** Runs the program in parse_all mode.
+
+    Instead of skipping unrecognized input, the run is rejected with an error
+    at the offset where input remains unconsumed. */
+    pub fn run_strict(&self, runtime: &mut Runtime) -> Result<Option<RefValue>, Error> {
+        let parse_all = std::mem::replace(&mut runtime.parse_all, true);
+        let ret = self.run(runtime);
+        runtime.parse_all = parse_all;
+        ret
+    }
+
+    /** Runs the program on a reader, streaming the main parselet's results.
+
+    Every result of the main parselet's loop is passed to `on_result` as soon as it is
+    produced, instead of being collected into a list. As the input buffer and memo table
+    are cleared after each result, memory usage stays bounded for huge inputs. */
+    pub fn run_streaming(
+        &self,
+        mut reader: Reader,
+        mut on_result: impl FnMut(RefValue),
+    ) -> Result<Option<RefValue>, Error> {
+        let mut runtime = Runtime::new(self, &mut reader);
+        runtime.on_result = Some(&mut on_result);
+        self.run(&mut runtime)
+    }
+
     pub fn run_from_reader(&self, mut reader: Reader) -> Result<Option<RefValue>, Error> {
         let mut runtime = Runtime::new(&self, &mut reader);
         self.run(&mut runtime)
@@ -85,6 +170,10 @@ impl Program {
         )))))
     }
 
+    pub fn run_from_bytes(&self, src: &[u8]) -> Result<Option<RefValue>, Error> {
+        self.run_from_reader(Reader::from_bytes(src)?)
+    }
+
     pub fn run_from_file(&self, filename: &str) -> Result<Option<RefValue>, Error> {
         if filename == "-" {
             self.run_from_reader(Reader::new(Box::new(BufReader::new(io::stdin()))))
@@ -1,10 +1,11 @@
 //! Holds overall required information for VM execution.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 use super::*;
 use crate::reader::{Offset, Reader};
-use crate::value::RefValue;
+use crate::value::{Dict, RefValue};
 
 /** Merges a program and a reader into one container.
 
@@ -13,10 +14,24 @@ Holds additional runtime information, like the stack or memoization table.
 pub struct Runtime<'program, 'reader> {
     pub(crate) program: &'program Program,
     pub(crate) reader: &'reader mut Reader,
+    pub(crate) on_result: Option<&'reader mut dyn FnMut(RefValue)>, // Streaming of main results
 
     pub(crate) memo: HashMap<(usize, usize), (Offset, Result<Accept, Reject>)>,
+    pub(crate) memo_limit: Option<usize>, // Maximum number of memo entries before eviction
+    pub(crate) memo_pinned: Vec<(usize, usize)>, // Memo entries of active left-recursions
     pub(crate) stack: Vec<Capture>,
-    pub debug: u8, // Debug level
+    pub(crate) indents: Vec<(usize, u32)>, // Indentation levels with the row they were opened
+    pub(crate) memoize: bool,              // Use memo table for non left-recursive parselets
+    pub(crate) gensym: usize,              // Counter for identifiers generated by gensym()
+    pub(crate) ops: u64,                   // Number of operations executed
+    pub(crate) max_ops: Option<u64>,       // Maximum number of operations to execute
+    #[cfg(feature = "random")]
+    pub(crate) rng: u64, // State of the pseudo-random number generator
+    pub parse_all: bool,                   // Reject input left unconsumed by main
+    pub debug: u8,                         // Debug level
+
+    // Call counts and cumulative durations per parselet, when profiling is enabled
+    pub(crate) profile: Option<HashMap<String, (usize, Duration)>>,
 }
 
 impl<'program, 'reader> Runtime<'program, 'reader> {
@@ -24,14 +39,129 @@ impl<'program, 'reader> Runtime<'program, 'reader> {
         Self {
             program,
             reader,
+            on_result: None,
             memo: HashMap::new(),
+            memo_limit: None,
+            memo_pinned: Vec::new(),
             stack: Vec::new(),
+            indents: Vec::new(),
+            memoize: true,
+            gensym: 0,
+            ops: 0,
+            max_ops: None,
+            #[cfg(feature = "random")]
+            rng: Self::seed(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |time| time.as_nanos() as u64),
+            ),
+            parse_all: false,
             debug: if let Ok(level) = std::env::var("TOKAY_DEBUG") {
                 level.parse::<u8>().unwrap_or_default()
             } else {
                 0
             },
+            profile: None,
+        }
+    }
+
+    /** Creates a runtime with a limited memo table.
+
+    Once the memo table holds `limit` entries, it is cleared before a new entry is inserted.
+    Entries of left-recursive parselets currently growing their results are kept. */
+    pub fn with_memo_limit(
+        program: &'program Program,
+        reader: &'reader mut Reader,
+        limit: usize,
+    ) -> Self {
+        Self {
+            memo_limit: Some(limit),
+            ..Self::new(program, reader)
+        }
+    }
+
+    /// Inserts a memo entry, evicting previous entries when the memo limit is reached.
+    pub(crate) fn memo_insert(
+        &mut self,
+        key: (usize, usize),
+        entry: (Offset, Result<Accept, Reject>),
+    ) {
+        if let Some(limit) = self.memo_limit {
+            if self.memo.len() >= limit && !self.memo.contains_key(&key) {
+                let pinned = &self.memo_pinned;
+                self.memo.retain(|key, _| pinned.contains(key));
+            }
+        }
+
+        self.memo.insert(key, entry);
+    }
+
+    /** Limits the number of operations executed by this runtime.
+
+    The run is rejected with an error once the limit is exceeded, which guards against
+    non-terminating programs, e.g. when running untrusted grammars. */
+    pub fn with_max_ops(mut self, max_ops: u64) -> Self {
+        self.max_ops = Some(max_ops);
+        self
+    }
+
+    /** Seeds the pseudo-random number generator used by builtins like `shuffle()`.
+
+    By default, it is seeded from the system time; a fixed seed makes runs reproducible. */
+    #[cfg(feature = "random")]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Self::seed(seed);
+        self
+    }
+
+    // Scrambles a seed using splitmix64, as the xorshift state must not be zero.
+    #[cfg(feature = "random")]
+    fn seed(seed: u64) -> u64 {
+        let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        (z ^ (z >> 31)).max(1)
+    }
+
+    /// Returns a pseudo-random number below `bound` using xorshift64*.
+    #[cfg(feature = "random")]
+    pub(crate) fn random(&mut self, bound: usize) -> usize {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+
+        (self.rng.wrapping_mul(0x2545f4914f6cdd1d) % bound as u64) as usize
+    }
+
+    /** Enables or disables memoization of parselet results.
+
+    Left-recursive parselets still use the memo table, as it is required for
+    growing their results. */
+    pub fn set_memoize(&mut self, memoize: bool) {
+        self.memoize = memoize;
+    }
+
+    /** Enables profiling of parselet calls.
+
+    Call counts and cumulative durations are accumulated per named parselet,
+    and can be retrieved using `Runtime::profile()`. */
+    pub fn enable_profiling(&mut self) {
+        self.profile.get_or_insert_with(HashMap::new);
+    }
+
+    /** Returns the profile as dict, with a dict holding `calls` and `time` in seconds
+    for every parselet called. The dict is empty when profiling is disabled. */
+    pub fn profile(&self) -> Dict {
+        let mut ret = Dict::new();
+
+        for (name, (calls, time)) in self.profile.iter().flatten() {
+            let mut entry = Dict::new();
+            entry.insert("calls".to_string(), RefValue::from(*calls as i64));
+            entry.insert("time".to_string(), RefValue::from(time.as_secs_f64()));
+            ret.insert(name.clone(), RefValue::from(entry));
         }
+
+        ret
     }
 
     pub fn load_stack(&mut self, stack: Vec<RefValue>) {